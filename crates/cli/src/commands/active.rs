@@ -5,12 +5,12 @@ use clap::{Parser, ValueEnum};
 use nael_core::dalamud::DalamudInstallation;
 
 #[derive(Debug, Default, Clone, ValueEnum)]
-enum OutputFormat {
-    /// Output only the name of the active branch.
+pub(super) enum OutputFormat {
+    /// Output only the name of the branch.
     #[default]
     Name,
 
-    /// Output the path to the active branch.
+    /// Output the path to the branch.
     Path,
 }
 
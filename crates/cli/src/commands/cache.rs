@@ -0,0 +1,113 @@
+use super::RunnableCommand;
+use crate::{AppState, BYTES_PER_MEBIBYTE, formatting::emphasis_text};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use nael_core::{
+    dalamud::DalamudInstallation,
+    fs::{
+        cache::{ArchiveCache, CacheEntry},
+        storage::AppStorage,
+    },
+};
+use std::collections::BTreeSet;
+
+/// The amount of characters of an archive checksum to show.
+const SHORT_CHECKSUM_LEN: usize = 12;
+
+/// Manage the cache of downloaded release archives.
+#[derive(Debug, Parser)]
+pub struct Cache {
+    #[clap(subcommand)]
+    cmd: CacheCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum CacheCommand {
+    /// List all cached release archives.
+    List,
+    /// Remove cached release archives that are not used by any installed branch.
+    Prune,
+    /// Remove all cached release archives.
+    Clear,
+}
+
+impl RunnableCommand for Cache {
+    async fn run(&self, state: &AppState) -> Result<()> {
+        let cache = ArchiveCache::new(
+            &state.storage.get_cache_directory()?,
+            state.storage.get_cache_size_limit(),
+        );
+
+        match self.cmd {
+            CacheCommand::List => {
+                let entries = cache.entries()?;
+                if entries.is_empty() {
+                    println!("No release archives are currently cached.");
+                    return Ok(());
+                }
+
+                println!(
+                    "Cached release archives ({} of {} MiB used):\n{}",
+                    format_mebibytes(cache.total_size()?),
+                    format_mebibytes(cache.size_limit()),
+                    entries
+                        .iter()
+                        .map(|entry| format!(" - {}", format_entry(entry)))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                );
+                Ok(())
+            }
+            CacheCommand::Prune => {
+                let mut used_checksums = BTreeSet::new();
+                for installation in
+                    DalamudInstallation::get_all(&state.storage)?.unwrap_or_default()
+                {
+                    if let Some(checksum) = installation.get_checksum()? {
+                        used_checksums.insert(checksum);
+                    }
+                }
+
+                let removed = cache.prune(|entry| !used_checksums.contains(&entry.sha256))?;
+                if removed.is_empty() {
+                    println!("No cached release archives needed to be removed.");
+                } else {
+                    println!(
+                        "Removed {} cached release archives, freeing {} MiB.",
+                        removed.len(),
+                        format_mebibytes(removed.iter().map(|entry| entry.size).sum())
+                    );
+                }
+                Ok(())
+            }
+            CacheCommand::Clear => {
+                cache.clear()?;
+                println!("Removed all cached release archives.");
+                println!(
+                    "Tip: run '{}' to only remove archives that are not used by an installed branch.",
+                    emphasis_text("nael cache prune")
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Format a size in bytes as mebibytes with a single decimal place.
+fn format_mebibytes(bytes: u64) -> String {
+    format!("{:.1}", bytes as f64 / BYTES_PER_MEBIBYTE as f64)
+}
+
+/// Format a cache entry as its version, short checksum and size.
+fn format_entry(entry: &CacheEntry) -> String {
+    let short_checksum = &entry.sha256[..SHORT_CHECKSUM_LEN.min(entry.sha256.len())];
+    format!(
+        "{} ({}, {} MiB)",
+        entry
+            .assembly_version
+            .as_deref()
+            .unwrap_or("unknown version"),
+        short_checksum,
+        format_mebibytes(entry.size)
+    )
+}
@@ -0,0 +1,22 @@
+use super::RunnableCommand;
+use crate::{AppState, Opts, shell::ShellKind};
+use anyhow::Result;
+use clap::{CommandFactory, Parser};
+use std::io::stdout;
+
+/// Output the script that enables shell completions, including the names of installed branches.
+///
+/// Completions are also included in the shell integration from 'nael init'.
+#[derive(Debug, Parser)]
+pub struct Completions {
+    /// The shell to output completions for.
+    #[clap(value_enum)]
+    shell: ShellKind,
+}
+
+impl RunnableCommand for Completions {
+    async fn run(&self, _: &AppState) -> Result<()> {
+        self.shell
+            .write_completions(Opts::command().get_name(), &mut stdout().lock())
+    }
+}
@@ -0,0 +1,63 @@
+use super::{
+    RunnableCommand, active::OutputFormat, find_project_file, get_installed_branch,
+    get_project_file,
+};
+use crate::{AppState, formatting::emphasis_text};
+use anyhow::{Context, Result, anyhow};
+use clap::Parser;
+use nael_core::dalamud::DalamudInstallation;
+
+/// Get the branch that applies to the current directory.
+///
+/// This is the branch from the nearest '.naelrc' or 'dalamud-version' project file in the current directory or any of
+/// its parent directories, falling back to the active branch when there is none.
+#[derive(Debug, Clone, Parser)]
+pub struct Current {
+    /// Only use the branch from the project file, without falling back to the active branch.
+    #[clap(short = 'p', long = "project")]
+    project: bool,
+
+    /// The format used for outputting the branch.
+    #[clap(short = 'f', long = "format", default_value_t, value_enum)]
+    format: OutputFormat,
+}
+
+impl RunnableCommand for Current {
+    async fn run(&self, state: &AppState) -> Result<()> {
+        let project_file = if self.project {
+            Some(get_project_file()?)
+        } else {
+            find_project_file()?
+        };
+        let installation = match project_file {
+            Some(project_file) => get_installed_branch(&project_file.branch_name, state)?,
+            None => {
+                let Some(installation) = DalamudInstallation::get_active(&state.storage)? else {
+                    return Err(anyhow!(
+                        "No project file was found and no active branch is set.\nTip: run '{}' to set an active branch.",
+                        emphasis_text("nael use <branch>")
+                    ));
+                };
+                installation
+            }
+        };
+
+        match self.format {
+            OutputFormat::Name => {
+                println!("{}", installation.branch_name);
+            }
+            OutputFormat::Path => {
+                println!(
+                    "{}",
+                    installation
+                        .get_location()?
+                        .context("could not determine branch location on disk")?
+                        .to_str()
+                        .context("could not parse location to str")?
+                );
+            }
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use super::{RunnableCommand, find_project_file, get_installed_branch};
+use crate::{
+    AppState, completion,
+    shell::{DALAMUD_HOME_ENV, ShellKind},
+};
+use anyhow::{Context, Result};
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use nael_core::fs::storage::AppStorage;
+
+/// Output the shell commands that point DALAMUD_HOME at a branch without changing the active branch.
+///
+/// The output is meant to be evaluated by the shell, e.g. `eval "$(nael env stg)"`.
+#[derive(Debug, Parser)]
+pub struct Env {
+    /// The branch to point DALAMUD_HOME at.
+    ///
+    /// When not given, the branch is read from the nearest '.naelrc' or 'dalamud-version' project file in the current
+    /// directory or any of its parent directories, falling back to the symlink of the active branch when there is none.
+    #[clap(add = ArgValueCandidates::new(completion::installed_branches))]
+    branch_name: Option<String>,
+
+    /// The shell to output commands for [default: detected from the SHELL environment variable].
+    #[clap(short = 's', long = "shell", value_enum)]
+    shell: Option<ShellKind>,
+}
+
+impl RunnableCommand for Env {
+    async fn run(&self, state: &AppState) -> Result<()> {
+        let branch_name = match &self.branch_name {
+            Some(branch_name) => Some(branch_name.clone()),
+            None => find_project_file()?.map(|project_file| project_file.branch_name),
+        };
+        let location = match branch_name {
+            Some(branch_name) => get_installed_branch(&branch_name, state)?
+                .get_location()?
+                .context("could not determine branch location on disk")?,
+            None => state
+                .storage
+                .get_active_branch_symlink()
+                .context("could not find active branch path")?,
+        };
+        let location = location
+            .to_str()
+            .context("could not parse location to str")?;
+
+        println!(
+            "{}",
+            self.shell
+                .unwrap_or_else(ShellKind::detect)
+                .set_env_command(DALAMUD_HOME_ENV, location)
+        );
+        Ok(())
+    }
+}
@@ -1,15 +1,20 @@
 use super::RunnableCommand;
-use crate::{AppState, formatting::emphasis_text};
-use anyhow::{Result, anyhow};
+use crate::{AppState, completion, formatting::emphasis_text};
+use anyhow::{Context, Result, anyhow};
 use clap::Parser;
+use clap_complete::ArgValueCandidates;
 use colored::Colorize;
-use nael_core::dalamud::{DalamudInstallation, DalamudVersionInfo, ReleaseSource};
+use nael_core::{
+    dalamud::{DalamudInstallation, DalamudVersionInfo, ReleaseSource, split_installation_name},
+    error::NaelError,
+};
 use std::str::FromStr;
 
 /// Show information about the specified branch.
 #[derive(Debug, Parser)]
 pub struct Info {
     /// The name of the branch to show information for.
+    #[clap(add = ArgValueCandidates::new(completion::installed_or_remote_branches))]
     branch_name: String,
 
     /// Output the information as JSON.
@@ -34,11 +39,13 @@ impl RunnableCommand for Info {
 /// Get release information for a local branch.
 fn get_release_info_local(args: &Info, state: &AppState) -> Result<()> {
     let Some(installation) = DalamudInstallation::get(&args.branch_name, &state.storage)? else {
-        return Err(anyhow!(
-            "The branch '{}' is not installed locally.\nTip: run can use the '{}' flag to see remote information instead.",
-            args.branch_name,
-            emphasis_text("--remote")
-        ));
+        return Err(NaelError::BranchNotInstalled(args.branch_name.clone())).with_context(|| {
+            format!(
+                "Unable to show local information for branch '{}'.\nTip: you can use the '{}' flag to see remote information instead.",
+                args.branch_name,
+                emphasis_text("--remote")
+            )
+        });
     };
 
     if args.json {
@@ -53,17 +60,38 @@ fn get_release_info_local(args: &Info, state: &AppState) -> Result<()> {
         };
 
         pretty_print_version_info(&args.branch_name, version_info, false);
+        if let Some(checksum) = installation.get_checksum()? {
+            println!("- Archive SHA-256: {checksum}");
+        }
         Ok(())
     }
 }
 
 /// Get release information for a remote branch.
 async fn get_release_info_remote(args: &Info, state: &AppState) -> Result<()> {
-    let raw_release_info: String = state
-        .release_source
-        .get_version_info_file(&args.branch_name)
-        .read_to_string()
-        .await?;
+    let version_info_file = match split_installation_name(&args.branch_name) {
+        (branch_name, Some(version)) => {
+            state
+                .release_source
+                .get_versioned_release(branch_name, version)
+                .await?
+                .ok_or_else(|| NaelError::ReleaseNotFound {
+                    branch: branch_name.to_owned(),
+                    version: Some(version.to_owned()),
+                })?
+                .version_info_file
+                .ok_or_else(|| {
+                    anyhow!("no version information is available for version '{version}' of branch '{branch_name}'")
+                })?
+        }
+        (branch_name, None) => {
+            state
+                .release_source
+                .get_version_info_file(branch_name)
+                .await?
+        }
+    };
+    let raw_release_info: String = version_info_file.read_to_string().await?;
 
     if args.json {
         println!("{}", raw_release_info.trim());
@@ -74,9 +102,9 @@ async fn get_release_info_remote(args: &Info, state: &AppState) -> Result<()> {
                 pretty_print_version_info(&args.branch_name, data, true);
                 Ok(())
             }
-            Err(_) => Err(anyhow!(
-                "could not get remote version information for the specified branch (does it exist?)"
-            )),
+            Err(err) => Err(err).context(
+                "could not get remote version information for the specified branch (does it exist?)",
+            ),
         }
     }
 }
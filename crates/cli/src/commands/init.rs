@@ -0,0 +1,61 @@
+use super::RunnableCommand;
+use crate::{
+    AppState, Opts,
+    shell::{DALAMUD_HOME_ENV, ShellKind},
+};
+use anyhow::{Context, Result};
+use clap::{CommandFactory, Parser};
+use nael_core::fs::storage::AppStorage;
+use std::io::{Write, stdout};
+
+/// Output the shell integration that sets DALAMUD_HOME and enables completions.
+///
+/// Add the integration to the shell configuration with `eval "$(nael init bash)"` in ~/.bashrc,
+/// `eval "$(nael init zsh)"` in ~/.zshrc, `nael init fish | source` in ~/.config/fish/config.fish or
+/// `nael init powershell | Out-String | Invoke-Expression` in the PowerShell profile.
+#[derive(Debug, Parser)]
+pub struct Init {
+    /// The shell to output the integration for.
+    #[clap(value_enum)]
+    shell: ShellKind,
+
+    /// Point DALAMUD_HOME at the branch from the nearest project file whenever the current directory changes.
+    #[clap(long = "auto-switch")]
+    auto_switch: bool,
+
+    /// Don't output shell completions.
+    #[clap(long = "no-completions")]
+    no_completions: bool,
+}
+
+impl RunnableCommand for Init {
+    async fn run(&self, state: &AppState) -> Result<()> {
+        let active_branch_symlink = state
+            .storage
+            .get_active_branch_symlink()
+            .context("could not find active branch path")?;
+        let active_branch_symlink = active_branch_symlink
+            .to_str()
+            .context("could not convert path to string for output")?;
+
+        let mut stdout = stdout().lock();
+        writeln!(
+            stdout,
+            "{}",
+            self.shell
+                .set_env_command(DALAMUD_HOME_ENV, active_branch_symlink)
+        )?;
+        if self.auto_switch {
+            write!(
+                stdout,
+                "{}",
+                self.shell.auto_switch_hook(active_branch_symlink)
+            )?;
+        }
+        if !self.no_completions {
+            self.shell
+                .write_completions(Opts::command().get_name(), &mut stdout)?;
+        }
+        Ok(())
+    }
+}
@@ -1,54 +1,96 @@
 use super::RunnableCommand;
-use crate::{AppState, formatting::emphasis_text};
-use anyhow::{Result, anyhow};
+use crate::{
+    AppState, completion, formatting::emphasis_text, release_source::LOCAL_FILE_SOURCE_NAME,
+};
+use anyhow::{Context, Result};
 use clap::Parser;
-use nael_core::dalamud::DalamudInstallation;
+use clap_complete::ArgValueCandidates;
+use nael_core::{
+    dalamud::{DalamudInstallation, LocalFileReleaseSource},
+    error::NaelError,
+};
+use std::path::PathBuf;
 
 /// Install a Dalamud release from the specified branch.
 #[derive(Debug, Parser)]
 pub struct Install {
     /// The branch to install.
+    ///
+    /// A specific version can be installed alongside the branch by using `<branch>@<version>`, where the version
+    /// is either an assembly version or a git sha.
+    #[clap(add = ArgValueCandidates::new(completion::remote_branches))]
     branch_name: String,
+
+    /// Install the branch from a release archive on disk instead of downloading it from the release source.
+    #[clap(long = "from-file", value_name = "PATH")]
+    from_file: Option<PathBuf>,
+
+    /// The version info file that belongs to the release archive given with '--from-file'.
+    #[clap(long = "version-info", value_name = "PATH", requires = "from_file")]
+    version_info: Option<PathBuf>,
 }
 
 impl RunnableCommand for Install {
     async fn run(&self, state: &AppState) -> Result<()> {
         if DalamudInstallation::exists(&self.branch_name, &state.storage)? {
-            return Err(anyhow!(
-                "Branch '{}' is already installed.\nTip: run '{}' to update it.",
-                self.branch_name,
-                emphasis_text(&format!("nael update {}", self.branch_name))
-            ));
+            return Err(NaelError::BranchAlreadyInstalled(self.branch_name.clone())).with_context(
+                || {
+                    format!(
+                        "Unable to install branch '{}'.\nTip: run '{}' to update it.",
+                        self.branch_name,
+                        emphasis_text(&format!("nael update {}", self.branch_name))
+                    )
+                },
+            );
         }
 
-        match DalamudInstallation::create(&self.branch_name, &state.storage, &state.release_source)
-            .await
-        {
-            Ok(installation) => {
-                match installation.get_version_info()? {
-                    Some(version_info) => {
-                        println!(
-                            "Successfully installed branch '{}' with version '{}'.",
-                            &installation.branch_name, &version_info.assembly_version
-                        );
-                    }
-                    _ => {
-                        println!(
-                            "Successfully installed branch '{}'",
-                            &installation.branch_name
-                        );
-                    }
+        let result = match &self.from_file {
+            Some(archive_path) => {
+                let mut release_source = LocalFileReleaseSource::new(archive_path)?;
+                if let Some(version_info_path) = &self.version_info {
+                    release_source = release_source.with_version_info_path(version_info_path)?;
                 }
+                DalamudInstallation::create(
+                    &self.branch_name,
+                    &state.storage,
+                    &release_source,
+                    LOCAL_FILE_SOURCE_NAME,
+                    &state.reporter,
+                )
+                .await
+            }
+            None => {
+                DalamudInstallation::create(
+                    &self.branch_name,
+                    &state.storage,
+                    &state.release_source,
+                    &state.source_name,
+                    &state.reporter,
+                )
+                .await
+            }
+        };
+
+        let installation = result
+            .with_context(|| format!("Failed to install the branch '{}'", self.branch_name))?;
+        match installation.get_version_info()? {
+            Some(version_info) => {
+                println!(
+                    "Successfully installed branch '{}' with version '{}'.",
+                    &installation.branch_name, &version_info.assembly_version
+                );
+            }
+            _ => {
                 println!(
-                    "Tip: run `{}` to select it as the active branch.",
-                    emphasis_text(&format!("nael use {}", self.branch_name))
+                    "Successfully installed branch '{}'",
+                    &installation.branch_name
                 );
-                Ok(())
             }
-            Err(_) => Err(anyhow!(
-                "Failed to install the branch '{}'",
-                self.branch_name
-            )),
         }
+        println!(
+            "Tip: run `{}` to select it as the active branch.",
+            emphasis_text(&format!("nael use {}", self.branch_name))
+        );
+        Ok(())
     }
 }
@@ -1,44 +1,167 @@
 use super::RunnableCommand;
-use crate::{AppState, formatting::selected_value};
-use anyhow::Result;
+use crate::{
+    AppState,
+    formatting::{emphasis_text, selected_value, warning_text},
+};
+use anyhow::{Context, Result};
 use clap::Parser;
-use nael_core::dalamud::DalamudInstallation;
+use colored::Colorize;
+use futures::{StreamExt, stream};
+use nael_core::dalamud::{DalamudInstallation, DalamudVersionInfo, ReleaseSource};
 
 const LIST_SYMBOL_ACTIVE: &str = "*";
 const LIST_SYMBOL_INACTIVE: &str = "-";
 
-/// List all installed branches.
+/// The maximum amount of remote branches to fetch version information for at the same time.
+const LIST_REMOTE_JOBS: usize = 4;
+
+/// List all installed branches, or the branches available from the release source.
 #[derive(Debug, Parser)]
-pub struct List;
+pub struct List {
+    /// List the branches available from the release source with their latest version and whether they are installed.
+    #[clap(short = 'r', long = "remote", default_value_t = false)]
+    remote: bool,
+}
 
 impl RunnableCommand for List {
     async fn run(&self, state: &AppState) -> Result<()> {
-        let Some(installations) = DalamudInstallation::get_all(&state.storage)? else {
-            println!("No branches are currently installed.");
-            return Ok(());
-        };
-
-        if installations.is_empty() {
-            println!("No branches are currently installed.");
-            return Ok(());
+        if self.remote {
+            list_remote(state).await
+        } else {
+            list_local(state)
         }
+    }
+}
 
-        let msg = format!(
-            "Installed branches:\n{}",
-            installations
-                .iter()
-                .map(|v| {
-                    if v.is_active().unwrap_or(false) {
-                        format!(" {LIST_SYMBOL_ACTIVE} {}", selected_value(&v.branch_name))
-                    } else {
-                        format!(" {LIST_SYMBOL_INACTIVE} {}", v.branch_name)
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join("\n")
+/// List all installed branches, marking the active branch.
+fn list_local(state: &AppState) -> Result<()> {
+    let Some(installations) = DalamudInstallation::get_all(&state.storage)? else {
+        println!("No branches are currently installed.");
+        return Ok(());
+    };
+
+    if installations.is_empty() {
+        println!("No branches are currently installed.");
+        return Ok(());
+    }
+
+    let msg = format!(
+        "Installed branches:\n{}",
+        installations
+            .iter()
+            .map(|v| {
+                if v.is_active().unwrap_or(false) {
+                    format!(" {LIST_SYMBOL_ACTIVE} {}", selected_value(&v.branch_name))
+                } else {
+                    format!(" {LIST_SYMBOL_INACTIVE} {}", v.branch_name)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    println!("{msg}");
+
+    Ok(())
+}
+
+/// List every branch of the release source with its latest version and the state of its local installation.
+///
+/// When the version information of a branch cannot be obtained, a warning is reported and its version is shown as
+/// unknown.
+async fn list_remote(state: &AppState) -> Result<()> {
+    let branches = state
+        .release_source
+        .get_branches()
+        .await
+        .context("Unable to list the branches of the release source")?;
+    if branches.is_empty() {
+        println!(
+            "The release source does not list its branches.\nTip: the branches of a distribution can be set with '{}' in the configuration file.",
+            emphasis_text("branches")
         );
-        println!("{msg}");
+        return Ok(());
+    }
+    let installations = DalamudInstallation::get_all(&state.storage)?.unwrap_or_default();
+
+    let results = stream::iter(branches)
+        .map(|branch_name| async move {
+            let version_info = match get_remote_version_info(&branch_name, state).await {
+                Ok(version_info) => Some(version_info),
+                Err(err) => {
+                    state.reporter.warn(&format!(
+                        "Failed to obtain remote version information for '{branch_name}': {err:#}"
+                    ));
+                    None
+                }
+            };
+            (branch_name, version_info)
+        })
+        .buffered(LIST_REMOTE_JOBS)
+        .collect::<Vec<_>>()
+        .await;
+
+    let name_width = results
+        .iter()
+        .map(|(branch_name, _)| branch_name.len())
+        .max()
+        .unwrap_or_default();
+    let version_width = results
+        .iter()
+        .map(|(_, version_info)| {
+            version_info
+                .as_ref()
+                .map_or("unknown".len(), |version_info| {
+                    version_info.assembly_version.len()
+                })
+        })
+        .max()
+        .unwrap_or_default();
 
-        Ok(())
+    println!("Remote branches:");
+    for (branch_name, remote_version_info) in &results {
+        let installation = installations
+            .iter()
+            .find(|installation| &installation.branch_name == branch_name);
+        let symbol =
+            if installation.is_some_and(|installation| installation.is_active().unwrap_or(false)) {
+                LIST_SYMBOL_ACTIVE
+            } else {
+                LIST_SYMBOL_INACTIVE
+            };
+        let version = remote_version_info
+            .as_ref()
+            .map_or("unknown", |version_info| &version_info.assembly_version);
+        let status = match (installation, remote_version_info) {
+            (None, _) => "".normal(),
+            (Some(_), None) => "installed".normal(),
+            (Some(installation), Some(remote_version_info)) => {
+                if installation.get_version_info().ok().flatten().as_ref()
+                    == Some(remote_version_info)
+                {
+                    selected_value("installed, up to date")
+                } else {
+                    warning_text("installed, out of date")
+                }
+            }
+        };
+        let line =
+            format!(" {symbol} {branch_name:<name_width$}  {version:<version_width$}  {status}");
+        println!("{}", line.trim_end());
     }
+
+    Ok(())
+}
+
+/// Get the latest version information of the given branch from the release source.
+async fn get_remote_version_info(
+    branch_name: &str,
+    state: &AppState,
+) -> Result<DalamudVersionInfo> {
+    DalamudVersionInfo::from_remote_file(
+        &state
+            .release_source
+            .get_version_info_file(branch_name)
+            .await?,
+    )
+    .await
 }
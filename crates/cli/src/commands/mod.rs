@@ -1,21 +1,88 @@
 mod active;
+mod cache;
+mod completions;
+mod current;
+mod env;
 mod info;
+mod init;
 mod install;
 mod list;
 mod remove;
+mod rollback;
+mod shell;
 mod symlink_path;
 mod update;
 mod update_all;
 mod r#use;
+mod verify;
 
 pub use {
-    active::Active, info::Info, install::Install, list::List, remove::Remove,
-    symlink_path::SymlinkPath, update::Update, update_all::UpdateAll, r#use::Use,
+    active::Active, cache::Cache, completions::Completions, current::Current, env::Env, info::Info,
+    init::Init, install::Install, list::List, remove::Remove, rollback::Rollback, shell::Shell,
+    symlink_path::SymlinkPath, update::Update, update_all::UpdateAll, r#use::Use, verify::Verify,
 };
 
-use crate::AppState;
-use anyhow::Result;
+use crate::{AppState, formatting::emphasis_text};
+use anyhow::{Context, Result, anyhow};
+use nael_core::{
+    dalamud::{DalamudInstallation, ProjectFile},
+    error::NaelError,
+    fs::storage::CompliantDiskStorage,
+};
+use std::env::current_dir;
 
 pub trait RunnableCommand {
     async fn run(&self, state: &AppState) -> Result<()>;
 }
+
+/// Find the nearest project file in the current directory or any of its parent directories.
+fn find_project_file() -> Result<Option<ProjectFile>> {
+    ProjectFile::find(&current_dir().context("unable to get current directory")?)
+}
+
+/// Get the nearest project file in the current directory or any of its parent directories, failing with a tip to
+/// create one when there is none.
+fn get_project_file() -> Result<ProjectFile> {
+    let current_dir = current_dir().context("unable to get current directory")?;
+    ProjectFile::find(&current_dir)?.ok_or_else(|| {
+        anyhow!(
+            "No project file was found in {:?} or any of its parent directories.\nTip: create a '{}' file containing a branch name to use one.",
+            current_dir,
+            emphasis_text(".naelrc")
+        )
+    })
+}
+
+/// Get the installation of the given branch, or of the branch from the nearest project file when none is given.
+///
+/// The project file the branch was read from is output to stderr.
+fn get_installation_or_project(
+    branch_name: Option<&str>,
+    state: &AppState,
+) -> Result<DalamudInstallation<CompliantDiskStorage>> {
+    match branch_name {
+        Some(branch_name) => get_installed_branch(branch_name, state),
+        None => {
+            let project_file = get_project_file()?;
+            eprintln!(
+                "Found branch '{}' in {:?}.",
+                project_file.branch_name, project_file.path
+            );
+            get_installed_branch(&project_file.branch_name, state)
+        }
+    }
+}
+
+/// Get the installation of the given branch, failing with a tip to install it when it isn't installed.
+fn get_installed_branch(
+    branch_name: &str,
+    state: &AppState,
+) -> Result<DalamudInstallation<CompliantDiskStorage>> {
+    DalamudInstallation::get(branch_name, &state.storage)?.ok_or_else(|| {
+        anyhow::Error::from(NaelError::BranchNotInstalled(branch_name.to_owned())).context(format!(
+            "Branch '{}' is not installed.\nTip: run '{}' to install it.",
+            branch_name,
+            emphasis_text(&format!("nael install {}", branch_name))
+        ))
+    })
+}
@@ -1,13 +1,15 @@
 use super::RunnableCommand;
-use crate::AppState;
-use anyhow::{Result, anyhow};
+use crate::{AppState, completion};
+use anyhow::{Context, Result};
 use clap::Parser;
-use nael_core::dalamud::DalamudInstallation;
+use clap_complete::ArgValueCandidates;
+use nael_core::{dalamud::DalamudInstallation, error::NaelError};
 
 /// Remove a branch from this system.
 #[derive(Debug, Parser)]
 pub struct Remove {
     /// The name of the branch to remove.
+    #[clap(add = ArgValueCandidates::new(completion::installed_branches))]
     branch_name: String,
 }
 
@@ -15,7 +17,8 @@ impl RunnableCommand for Remove {
     async fn run(&self, state: &AppState) -> Result<()> {
         let Some(installation) = DalamudInstallation::get(&self.branch_name, &state.storage)?
         else {
-            return Err(anyhow!("Branch '{}' is not installed", &self.branch_name));
+            return Err(NaelError::BranchNotInstalled(self.branch_name.clone()))
+                .with_context(|| format!("Unable to remove branch '{}'", self.branch_name));
         };
 
         installation.remove()?;
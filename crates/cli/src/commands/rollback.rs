@@ -0,0 +1,53 @@
+use super::RunnableCommand;
+use crate::{AppState, completion, formatting::emphasis_text};
+use anyhow::{Context, Result, anyhow};
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use nael_core::{dalamud::DalamudInstallation, error::NaelError};
+
+/// Restore the previous build of a local branch.
+#[derive(Debug, Parser)]
+pub struct Rollback {
+    /// The branch to roll back.
+    ///
+    /// Leave blank to imply the currently active branch.
+    #[clap(add = ArgValueCandidates::new(completion::installed_branches))]
+    branch_name: Option<String>,
+}
+
+impl RunnableCommand for Rollback {
+    async fn run(&self, state: &AppState) -> Result<()> {
+        let branch_name = match self.branch_name.clone() {
+            Some(branch_name) => Ok(branch_name),
+            None => {
+                if let Some(active) = DalamudInstallation::get_active(&state.storage)? {
+                    println!(
+                        "Branch name not specified - inferring from active installation to be {}.",
+                        emphasis_text(&active.branch_name)
+                    );
+                    Ok(active.branch_name)
+                } else {
+                    Err(anyhow!(
+                        "No branch name specified. You must set a branch as active to use the rollback command without arguments.\nTip: run '{}' to roll back a specific branch.",
+                        emphasis_text("nael rollback <branch>")
+                    ))
+                }
+            }
+        }?;
+
+        let Some(installation) = DalamudInstallation::get(&branch_name, &state.storage)? else {
+            return Err(NaelError::BranchNotInstalled(branch_name.clone()))
+                .with_context(|| format!("Unable to roll back branch '{}'", branch_name));
+        };
+
+        let installation = installation.rollback()?;
+        match installation.get_version_info() {
+            Ok(Some(version_info)) => println!(
+                "Successfully rolled back branch '{}' to version '{}'.",
+                branch_name, version_info.assembly_version
+            ),
+            _ => println!("Successfully rolled back branch '{}'.", branch_name),
+        }
+        Ok(())
+    }
+}
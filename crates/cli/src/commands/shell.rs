@@ -0,0 +1,46 @@
+use super::{RunnableCommand, get_installation_or_project};
+use crate::{AppState, completion, shell::DALAMUD_HOME_ENV};
+use anyhow::{Context, Result};
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use std::{env, ffi::OsString, process::Command};
+
+/// Start a new shell with DALAMUD_HOME pointing at a branch without changing the active branch.
+#[derive(Debug, Parser)]
+pub struct Shell {
+    /// The branch to point DALAMUD_HOME at.
+    ///
+    /// When not given, the branch is read from the nearest '.naelrc' or 'dalamud-version' project file in the current
+    /// directory or any of its parent directories.
+    #[clap(add = ArgValueCandidates::new(completion::installed_branches))]
+    branch_name: Option<String>,
+}
+
+impl RunnableCommand for Shell {
+    async fn run(&self, state: &AppState) -> Result<()> {
+        let installation = get_installation_or_project(self.branch_name.as_deref(), state)?;
+        let location = installation
+            .get_location()?
+            .context("could not determine branch location on disk")?;
+
+        let shell = get_user_shell();
+        eprintln!(
+            "Starting a shell with branch '{}', exit it to return.",
+            installation.branch_name
+        );
+        Command::new(&shell)
+            .env(DALAMUD_HOME_ENV, &location)
+            .status()
+            .with_context(|| format!("failed to start shell {shell:?}"))?;
+        Ok(())
+    }
+}
+
+/// Get the shell of the current user, falling back to the default shell of the platform.
+fn get_user_shell() -> OsString {
+    if cfg!(windows) {
+        env::var_os("COMSPEC").unwrap_or_else(|| "powershell.exe".into())
+    } else {
+        env::var_os("SHELL").unwrap_or_else(|| "/bin/sh".into())
+    }
+}
@@ -1,11 +1,9 @@
 use super::RunnableCommand;
-use crate::{
-    AppState,
-    formatting::{emphasis_text, warning_text},
-};
-use anyhow::{Result, anyhow};
+use crate::{AppState, completion, formatting::emphasis_text, release_source::AppReleaseSource};
+use anyhow::{Context, Result, anyhow};
 use clap::Parser;
-use nael_core::{dalamud::DalamudInstallation, fs::storage::AppStorage};
+use clap_complete::ArgValueCandidates;
+use nael_core::{dalamud::DalamudInstallation, error::NaelError, fs::storage::AppStorage};
 
 /// Update a local branch to the latest version.
 #[derive(Debug, Parser)]
@@ -13,6 +11,7 @@ pub struct Update {
     /// The branch to update.
     ///
     /// Leave blank to imply the currently active branch.
+    #[clap(add = ArgValueCandidates::new(completion::installed_branches))]
     branch_name: Option<String>,
 
     /// Forcefully update regardless of the current local or remote version information.
@@ -55,16 +54,48 @@ impl RunnableCommand for Update {
         }?;
 
         let Some(installation) = DalamudInstallation::get(&branch_name, &state.storage)? else {
-            return Err(anyhow!(
-                "Branch '{}' is not installed.\nTip: run '{}' to try and install it.",
+            return Err(NaelError::BranchNotInstalled(branch_name.clone())).with_context(|| {
+                format!(
+                    "Unable to update branch '{}'.\nTip: run '{}' to try and install it.",
+                    branch_name,
+                    emphasis_text(&format!("nael install {}", branch_name))
+                )
+            });
+        };
+
+        // Pinned installations never get updated.
+        if let Some(version) = installation.get_pinned_version() {
+            return Err(NaelError::BranchPinned {
+                branch: installation.get_remote_branch_name().to_owned(),
+                version: version.to_owned(),
+            })
+            .with_context(|| {
+                format!(
+                    "Branch '{}' is pinned to version '{}' and will not be updated.\nTip: run '{}' to install the latest version.",
+                    installation.get_remote_branch_name(),
+                    version,
+                    emphasis_text(&format!(
+                        "nael install {}",
+                        installation.get_remote_branch_name()
+                    ))
+                )
+            });
+        }
+
+        // Installations from a local file are only updated from a release source when one is selected.
+        let Some((source_name, release_source)) = state.get_installation_source(&installation)?
+        else {
+            println!(
+                "Branch '{}' was installed from a local file and will not be updated.\nTip: run '{}' to update it from a release source.",
                 branch_name,
-                emphasis_text(&format!("nael install {}", branch_name))
-            ));
+                emphasis_text(&format!("nael update {} --source <source>", branch_name))
+            );
+            return Ok(());
         };
 
         // Handle check for update.
         if self.check {
-            if is_up_to_date(&installation, state).await {
+            if is_up_to_date(&installation, &release_source, state).await {
                 println!("Branch is up to date.")
             } else {
                 println!("Branch is out of date.");
@@ -78,65 +109,75 @@ impl RunnableCommand for Update {
                 "Forcefully updating branch '{}' to latest version.",
                 branch_name
             );
-            return update_branch(&branch_name, installation, state).await;
+            return update_branch(
+                &branch_name,
+                installation,
+                &source_name,
+                &release_source,
+                state,
+            )
+            .await;
         }
 
         // Handle regular update.
-        if is_up_to_date(&installation, state).await {
+        if is_up_to_date(&installation, &release_source, state).await {
             println!("Branch is already up to date.");
             return Ok(());
         }
 
-        update_branch(&branch_name, installation, state).await
+        update_branch(
+            &branch_name,
+            installation,
+            &source_name,
+            &release_source,
+            state,
+        )
+        .await
     }
 }
 
-/// Handle updating the given installation to the latest version and printing messages to Stdout and Stderr accordingly.
+/// Handle updating the given installation to the latest version from the given release source and printing messages to
+/// Stdout and Stderr accordingly.
 async fn update_branch<S: AppStorage>(
     branch_name: &str,
     installation: DalamudInstallation<S>,
+    source_name: &str,
+    release_source: &AppReleaseSource,
     state: &AppState,
 ) -> Result<()> {
-    if installation.update(&state.release_source).await.is_err() {
-        return Err(anyhow!("Failed to update branch '{}'", &branch_name));
-    }
+    installation
+        .update(release_source, source_name, &state.reporter)
+        .await
+        .with_context(|| format!("Failed to update branch '{}'", &branch_name))?;
     println!("Updated branch to the latest version.");
     Ok(())
 }
 
-/// Check for whether or not the given installation/branch is up to date or not.
+/// Check for whether or not the given installation/branch is up to date with the given release source or not.
 ///
 /// When any part of the checking for remote/local version information fails, this function will
-/// output a warning to Stderr and indicate the release is out of date.
+/// report a warning and indicate the release is out of date.
 async fn is_up_to_date<S: AppStorage>(
     installation: &DalamudInstallation<S>,
+    release_source: &AppReleaseSource,
     state: &AppState,
 ) -> bool {
     let version_info = match installation.get_version_info() {
         Ok(version_info) => version_info,
         Err(err) => {
-            eprintln!(
-                "{}",
-                warning_text(&format!(
-                    "Warning: Failed to obtain version information: {err:?}\n"
-                ))
-            );
+            state
+                .reporter
+                .warn(&format!("Failed to obtain version information: {err:#}"));
             None
         }
     };
 
-    let remote_version_info = match installation
-        .get_remote_version_info(&state.release_source)
-        .await
-    {
+    let remote_version_info = match installation.get_remote_version_info(release_source).await {
         Ok(remote_version_info) => remote_version_info,
         Err(err) => {
-            eprintln!(
-                "{}",
-                warning_text(&format!(
-                    "Warning: Failed to obtain remote version information: {err:?}\n"
-                ))
-            );
+            state.reporter.warn(&format!(
+                "Failed to obtain remote version information: {err:#}"
+            ));
             None
         }
     };
@@ -1,12 +1,42 @@
 use super::RunnableCommand;
-use crate::{AppState, formatting::warning_text};
-use anyhow::{Result, anyhow};
+use crate::{
+    AppState,
+    formatting::{emphasis_text, error_text, selected_value, warning_text},
+    release_source::AppReleaseSource,
+};
+use anyhow::{Error, Result, anyhow};
 use clap::Parser;
-use nael_core::{dalamud::DalamudInstallation, fs::storage::AppStorage};
+use colored::Colorize;
+use futures::{StreamExt, stream};
+use nael_core::{
+    dalamud::DalamudInstallation, fs::storage::AppStorage, progress::ProgressReporter,
+};
+use std::{num::NonZeroUsize, sync::Arc};
 
 /// Update all local branches to the latest version.
 #[derive(Debug, Parser)]
-pub struct UpdateAll;
+pub struct UpdateAll {
+    /// The maximum amount of branches to check and update at the same time.
+    #[clap(short = 'j', long = "jobs", default_value = "4")]
+    jobs: NonZeroUsize,
+}
+
+/// The result of updating a single branch.
+enum UpdateOutcome {
+    /// The branch was updated, from and to the given versions when they are known.
+    Updated {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// The branch was already up to date with the given version.
+    UpToDate(String),
+    /// The branch is pinned to the given version and was not checked.
+    Pinned(String),
+    /// The branch was installed from a local file and was not checked.
+    LocalFile,
+    /// Checking or updating the branch failed.
+    Failed(Error),
+}
 
 impl RunnableCommand for UpdateAll {
     async fn run(&self, state: &AppState) -> Result<()> {
@@ -14,88 +44,131 @@ impl RunnableCommand for UpdateAll {
             return Err(anyhow!("No dalamud installations detected."));
         };
 
-        let mut has_updated = false;
-        for install in installations {
-            if is_up_to_date(&install, state).await {
-                continue;
-            }
+        // Each branch is updated in its own task so that one branch extracting never holds up the downloads of others.
+        let mut results = stream::iter(installations)
+            .map(|installation| {
+                let branch_name = installation.branch_name.clone();
+                let source = state.get_installation_source(&installation);
+                let task = tokio::spawn(update_installation(
+                    installation,
+                    source,
+                    Arc::clone(&state.reporter),
+                ));
+                async move {
+                    let outcome = task
+                        .await
+                        .unwrap_or_else(|err| UpdateOutcome::Failed(err.into()));
+                    (branch_name, outcome)
+                }
+            })
+            .buffer_unordered(self.jobs.get())
+            .collect::<Vec<_>>()
+            .await;
+        results.sort_by(|(a, _), (b, _)| a.cmp(b));
 
-            update_branch(&install.branch_name, install.clone(), state).await?;
-            println!("Updated {} successfully", &install.branch_name);
-            has_updated = true;
+        if results.is_empty() {
+            println!("No branches are currently installed.");
+            return Ok(());
         }
+        print_summary(&results);
 
-        if has_updated {
-            println!("One or more installations were successfully updated");
-        } else {
-            println!("All installations are up to date.")
+        let failed = results
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, UpdateOutcome::Failed(_)))
+            .count();
+        if failed > 0 {
+            return Err(anyhow!(
+                "{} of {} branches failed to update",
+                failed,
+                results.len()
+            ));
         }
         Ok(())
     }
 }
 
-/// Handle updating the given installation to the latest version and printing messages to Stdout and Stderr accordingly.
-async fn update_branch<S: AppStorage>(
-    branch_name: &str,
+/// Check the given installation for updates from the release source obtained by
+/// [`AppState::get_installation_source`] and update it when it is out of date.
+///
+/// When the local or remote version information cannot be obtained, a warning is reported and the branch is
+/// assumed to be out of date.
+async fn update_installation<S: AppStorage>(
     installation: DalamudInstallation<S>,
-    state: &AppState,
-) -> Result<()> {
-    if installation.update(&state.release_source).await.is_err() {
-        return Err(anyhow!("Failed to update branch '{}'", &branch_name));
+    source: Result<Option<(String, AppReleaseSource)>>,
+    reporter: Arc<dyn ProgressReporter>,
+) -> UpdateOutcome {
+    if let Some(version) = installation.get_pinned_version() {
+        return UpdateOutcome::Pinned(version.to_owned());
     }
-    Ok(())
-}
-
-/// Check for whether or not the given installation/branch is up to date or not.
-///
-/// When any part of the checking for remote/local version information fails, this function will
-/// output a warning to Stderr and indicate the release is out of date.
-async fn is_up_to_date<S: AppStorage>(
-    installation: &DalamudInstallation<S>,
-    state: &AppState,
-) -> bool {
-    let version_info = match installation.get_version_info() {
-        Ok(version_info) => version_info,
-        Err(err) => {
-            eprintln!(
-                "{}",
-                warning_text(&format!(
-                    "Warning: Failed to obtain version information: {err:?}\n"
-                ))
-            );
-            None
-        }
+    let (source_name, release_source) = match source {
+        Ok(Some(source)) => source,
+        Ok(None) => return UpdateOutcome::LocalFile,
+        Err(err) => return UpdateOutcome::Failed(err),
     };
 
-    let remote_version_info = match installation
-        .get_remote_version_info(&state.release_source)
+    let version_info = installation.get_version_info().unwrap_or_else(|err| {
+        reporter.warn(&format!(
+            "Failed to obtain version information for '{}': {err:#}",
+            installation.branch_name
+        ));
+        None
+    });
+    let remote_version_info = installation
+        .get_remote_version_info(&release_source)
         .await
-    {
-        Ok(remote_version_info) => remote_version_info,
-        Err(err) => {
-            eprintln!(
-                "{}",
-                warning_text(&format!(
-                    "Warning: Failed to obtain remote version information: {err:?}\n"
-                ))
-            );
+        .unwrap_or_else(|err| {
+            reporter.warn(&format!(
+                "Failed to obtain remote version information for '{}': {err:#}",
+                installation.branch_name
+            ));
             None
+        });
+
+    if let (Some(version_info), Some(remote_version_info)) = (&version_info, &remote_version_info) {
+        if version_info == remote_version_info {
+            return UpdateOutcome::UpToDate(version_info.assembly_version.clone());
         }
-    };
+    }
 
-    let Some(version_info) = version_info else {
-        println!(
-            "No local version information was found for branch, it will be assumed out of date..."
-        );
-        return false;
-    };
+    match installation
+        .update(&release_source, &source_name, &reporter)
+        .await
+    {
+        Ok(installation) => UpdateOutcome::Updated {
+            from: version_info.map(|version_info| version_info.assembly_version),
+            to: installation
+                .get_version_info()
+                .ok()
+                .flatten()
+                .map(|version_info| version_info.assembly_version),
+        },
+        Err(err) => UpdateOutcome::Failed(err.into()),
+    }
+}
 
-    let Some(remote_version_info) = remote_version_info else {
-        println!(
-            "No remote version information was found for branch, it will be assumed out of date..."
-        );
-        return false;
-    };
+/// Output a table of the outcome of every branch to stdout.
+fn print_summary(results: &[(String, UpdateOutcome)]) {
+    let name_width = results
+        .iter()
+        .map(|(branch_name, _)| branch_name.len())
+        .max()
+        .unwrap_or_default();
 
-    version_info == remote_version_info
+    println!("{}", emphasis_text("Summary:"));
+    for (branch_name, outcome) in results {
+        let result = match outcome {
+            UpdateOutcome::Updated { from, to } => selected_value(&format!(
+                "updated {} -> {}",
+                from.as_deref().unwrap_or("unknown"),
+                to.as_deref().unwrap_or("unknown")
+            )),
+            UpdateOutcome::UpToDate(version) => format!("up to date ({version})").normal(),
+            UpdateOutcome::Pinned(version) => {
+                warning_text(&format!("skipped (pinned to {version})"))
+            }
+            UpdateOutcome::LocalFile => warning_text("skipped (installed from a local file)"),
+            UpdateOutcome::Failed(err) => error_text(&format!("failed: {err:#}")),
+        };
+        println!(" {branch_name:<name_width$}  {result}");
+    }
 }
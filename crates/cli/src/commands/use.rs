@@ -1,38 +1,30 @@
-use crate::{AppState, formatting::emphasis_text};
-
-use super::RunnableCommand;
-use anyhow::{Result, anyhow};
+use super::{RunnableCommand, get_installation_or_project};
+use crate::{AppState, completion};
+use anyhow::{Context, Result};
 use clap::Parser;
-use nael_core::dalamud::DalamudInstallation;
+use clap_complete::ArgValueCandidates;
 
 /// Switch the currently active branch.
 #[derive(Debug, Parser)]
 pub struct Use {
     /// The branch name to use.
-    branch_name: String,
+    ///
+    /// When not given, the branch is read from the nearest '.naelrc' or 'dalamud-version' project file in the current
+    /// directory or any of its parent directories.
+    #[clap(add = ArgValueCandidates::new(completion::installed_branches))]
+    branch_name: Option<String>,
 }
 
 impl RunnableCommand for Use {
     async fn run(&self, state: &AppState) -> Result<()> {
-        let Some(installation) = DalamudInstallation::get(&self.branch_name, &state.storage)?
-        else {
-            return Err(anyhow!(
-                "Branch '{}' is not installed.\nTip: run '{}' to install it.",
-                self.branch_name,
-                emphasis_text(&format!("nael install {}", self.branch_name))
-            ));
-        };
-
-        match installation.set_active() {
-            Err(err) => Err(anyhow!(
-                "Failed to use switch to branch '{}': {}",
-                &self.branch_name,
-                err
-            )),
-            _ => {
-                println!("Successfully set branch '{}' as active.", &self.branch_name);
-                Ok(())
-            }
-        }
+        let installation = get_installation_or_project(self.branch_name.as_deref(), state)?;
+        installation.set_active().with_context(|| {
+            format!("Failed to switch to branch '{}'", &installation.branch_name)
+        })?;
+        println!(
+            "Successfully set branch '{}' as active.",
+            &installation.branch_name
+        );
+        Ok(())
     }
 }
@@ -0,0 +1,110 @@
+use super::RunnableCommand;
+use crate::{
+    AppState, completion,
+    formatting::{emphasis_text, warning_text},
+};
+use anyhow::{Context, Result, anyhow};
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use nael_core::{dalamud::DalamudInstallation, error::NaelError, fs::manifest::ManifestDiff};
+
+/// Check a local branch for missing, extra or modified files.
+#[derive(Debug, Parser)]
+pub struct Verify {
+    /// The branch to verify.
+    ///
+    /// Leave blank to imply the currently active branch.
+    #[clap(add = ArgValueCandidates::new(completion::installed_branches))]
+    branch_name: Option<String>,
+
+    /// Repair the branch by extracting a fresh copy of its release when any problems are found.
+    #[clap(short = 'r', long = "repair", default_value_t = false)]
+    repair: bool,
+}
+
+impl RunnableCommand for Verify {
+    async fn run(&self, state: &AppState) -> Result<()> {
+        let branch_name = match self.branch_name.clone() {
+            Some(branch_name) => Ok(branch_name),
+            None => {
+                if let Some(active) = DalamudInstallation::get_active(&state.storage)? {
+                    println!(
+                        "Branch name not specified - inferring from active installation to be {}.",
+                        emphasis_text(&active.branch_name)
+                    );
+                    Ok(active.branch_name)
+                } else {
+                    Err(anyhow!(
+                        "No branch name specified. You must set a branch as active to use the verify command without arguments.\nTip: run '{}' to verify a specific branch.",
+                        emphasis_text("nael verify <branch>")
+                    ))
+                }
+            }
+        }?;
+
+        let Some(installation) = DalamudInstallation::get(&branch_name, &state.storage)? else {
+            return Err(NaelError::BranchNotInstalled(branch_name.clone()))
+                .with_context(|| format!("Unable to verify branch '{}'", branch_name));
+        };
+
+        let diff = installation.verify()?;
+        if diff.is_empty() {
+            println!("Branch '{}' is intact.", branch_name);
+            return Ok(());
+        }
+        print_diff(&diff);
+
+        if !self.repair {
+            return Err(anyhow!(
+                "Branch '{}' does not match its manifest.\nTip: run '{}' to repair it.",
+                branch_name,
+                emphasis_text(&format!("nael verify {} --repair", branch_name))
+            ));
+        }
+
+        // Installations from a local file can still be repaired from the cache without a release source.
+        let release_source = state
+            .get_installation_source(&installation)?
+            .map(|(_, release_source)| release_source);
+        let installation = match installation
+            .repair(release_source.as_ref(), &state.reporter)
+            .await
+        {
+            Err(err @ NaelError::MissingReleaseSource(_)) => {
+                return Err(err).with_context(|| {
+                    format!(
+                        "Branch '{}' was installed from a local file that is no longer cached.\nTip: run '{}' to repair it from a release source.",
+                        branch_name,
+                        emphasis_text(&format!("nael verify {} --repair --source <source>", branch_name))
+                    )
+                });
+            }
+            result => result?,
+        };
+        if !installation.verify()?.is_empty() {
+            return Err(anyhow!(
+                "Branch '{}' still does not match its manifest after repairing",
+                branch_name
+            ));
+        }
+        println!("Successfully repaired branch '{}'.", branch_name);
+        Ok(())
+    }
+}
+
+/// Output every difference between a branch and its manifest to stdout.
+fn print_diff(diff: &ManifestDiff) {
+    for (label, paths) in [
+        ("Missing", &diff.missing),
+        ("Modified", &diff.modified),
+        ("Extra", &diff.extra),
+    ] {
+        if paths.is_empty() {
+            continue;
+        }
+        println!("{} files:", warning_text(label));
+        for path in paths {
+            println!(" - {path}");
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    APP_NAME, APP_ORGANIZATION, APP_QUALIFIER,
+    config::Config,
+    release_source::{AppReleaseSource, GOATCORP_SOURCE_NAME},
+};
+use anyhow::Result;
+use clap_complete::CompletionCandidate;
+use nael_core::{
+    dalamud::{DalamudInstallation, ReleaseSource},
+    fs::storage::{AppStorage, CompliantDiskStorage},
+    net::configure_client,
+};
+use std::{
+    env, fs,
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::{runtime::Handle, task::block_in_place};
+
+/// The environment variable that selects the release source, the same as the `--source` flag.
+const SOURCE_ENV: &str = "NAEL_SOURCE";
+
+/// The flags that make a command use remote branches instead of installed ones.
+const REMOTE_LONG_FLAG: &str = "--remote";
+const REMOTE_SHORT_FLAG: &str = "-r";
+
+/// How long the branch names of a release source are cached for before they are fetched again.
+const REMOTE_BRANCHES_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The connect and read timeout of network requests made while completing, kept short as the shell waits on them.
+const COMPLETION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Get the names of every installed branch as completion candidates, or none when they can't be read.
+pub fn installed_branches() -> Vec<CompletionCandidate> {
+    let storage = Arc::new(CompliantDiskStorage::new(
+        APP_QUALIFIER,
+        APP_ORGANIZATION,
+        APP_NAME,
+    ));
+    DalamudInstallation::get_all(&storage)
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|installation| CompletionCandidate::new(installation.branch_name))
+        .collect()
+}
+
+/// Get the names of every branch of the release source as completion candidates, or none when they can't be obtained.
+///
+/// The command line being completed hasn't been parsed, so the release source is selected from the environment variable
+/// or the configuration file instead of the `--source` flag. Branch names are cached for [`REMOTE_BRANCHES_CACHE_TTL`]
+/// and fetched with short timeouts and no retries, falling back to an expired cache when fetching fails, so that
+/// completions stay responsive offline and don't use up API rate limits.
+pub fn remote_branches() -> Vec<CompletionCandidate> {
+    get_remote_branches()
+        .unwrap_or_default()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Get the names of every installed branch, and also every branch of the release source when the command line being
+/// completed contains the `--remote` flag.
+pub fn installed_or_remote_branches() -> Vec<CompletionCandidate> {
+    let mut candidates = installed_branches();
+    if !env::args().any(|arg| arg == REMOTE_LONG_FLAG || arg == REMOTE_SHORT_FLAG) {
+        return candidates;
+    }
+    for candidate in remote_branches() {
+        if !candidates
+            .iter()
+            .any(|installed| installed.get_value() == candidate.get_value())
+        {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// Get the branch names of the release source from the cache, fetching and caching them when the cache has expired.
+fn get_remote_branches() -> Result<Vec<String>> {
+    let storage = CompliantDiskStorage::new(APP_QUALIFIER, APP_ORGANIZATION, APP_NAME);
+    let config = Config::load(&storage.get_config_file_path()?)?;
+    let source_name = env::var(SOURCE_ENV)
+        .ok()
+        .or_else(|| config.default_source.clone())
+        .unwrap_or_else(|| GOATCORP_SOURCE_NAME.to_owned());
+    let cache_path = storage.get_remote_branches_cache_path(&source_name)?;
+    let cached_branches = read_cached_branches(&cache_path);
+    if let Some((branches, modified)) = &cached_branches {
+        if modified
+            .elapsed()
+            .is_ok_and(|age| age < REMOTE_BRANCHES_CACHE_TTL)
+        {
+            return Ok(branches.clone());
+        }
+    }
+
+    configure_client(
+        config
+            .network
+            .client_settings()
+            .with_connect_timeout(COMPLETION_TIMEOUT)
+            .with_read_timeout(COMPLETION_TIMEOUT)
+            .with_max_retries(0),
+    )?;
+    let release_source = AppReleaseSource::from_name(&source_name, &config)?;
+    match block_in_place(|| Handle::current().block_on(release_source.get_branches())) {
+        Ok(branches) => {
+            let _ = fs::write(&cache_path, serde_json::to_string(&branches)?);
+            Ok(branches)
+        }
+        Err(err) => cached_branches.map(|(branches, _)| branches).ok_or(err),
+    }
+}
+
+/// Read the cached branch names at the given path along with when they were cached.
+fn read_cached_branches(path: &Path) -> Option<(Vec<String>, SystemTime)> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()?;
+    let branches = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    Some((branches, modified))
+}
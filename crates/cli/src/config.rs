@@ -0,0 +1,151 @@
+use anyhow::{Context, Result};
+use nael_core::net::ClientSettings;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The user configuration file, every option is optional and falls back to a default when not set.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// The name of the release source to use when one isn't given on the command line.
+    pub default_source: Option<String>,
+
+    /// The amount of previous builds to keep for each branch when updating.
+    pub history_retention: Option<usize>,
+
+    /// The maximum total size of cached release archives in mebibytes.
+    pub cache_size_limit: Option<u64>,
+
+    /// The maximum total uncompressed size of a release archive in mebibytes.
+    pub extract_size_limit: Option<u64>,
+
+    /// The maximum amount of entries of a release archive.
+    pub extract_entry_limit: Option<u64>,
+
+    /// Settings for network requests.
+    pub network: NetworkConfig,
+
+    /// Additional named release sources.
+    pub sources: BTreeMap<String, SourceConfig>,
+}
+
+/// Settings for network requests.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// The time in seconds allowed to establish a connection.
+    pub connect_timeout: Option<u64>,
+
+    /// The time in seconds allowed between receiving two pieces of data before a request is considered stalled.
+    pub read_timeout: Option<u64>,
+
+    /// The amount of times a failed request is retried.
+    pub retries: Option<u32>,
+
+    /// The URL of the proxy to send every request through.
+    pub proxy: Option<String>,
+
+    /// The path to a file of PEM encoded certificates to trust in addition to the built-in root certificates.
+    pub ca_bundle: Option<PathBuf>,
+
+    /// The user agent to send with every request.
+    pub user_agent: Option<String>,
+
+    /// Additional headers to send with every request.
+    pub headers: BTreeMap<String, String>,
+}
+
+/// The kind of release source a named source is.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceKind {
+    /// A distribution that follows the same layout as `goatcorp/dalamud-distrib`.
+    #[default]
+    Distrib,
+
+    /// A Kamori instance.
+    Kamori,
+}
+
+/// A named release source from the configuration file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SourceConfig {
+    /// The kind of release source.
+    #[serde(default)]
+    pub kind: SourceKind,
+
+    /// The base URL of the release source.
+    pub base_url: String,
+
+    /// The name of the branch stored at the root of the distribution.
+    pub root_branch_name: Option<String>,
+
+    /// The filename of the release archive inside of each branch.
+    pub archive_filename: Option<String>,
+
+    /// The filename of the version info file inside of each branch.
+    pub version_filename: Option<String>,
+
+    /// The directory inside of each branch that contains specific versions of the branch.
+    pub versions_directory: Option<String>,
+
+    /// The filename of the published SHA-256 checksum of the release archive inside of each branch.
+    pub checksum_filename: Option<String>,
+
+    /// The names of the branches the distribution has, used when listing remote branches.
+    pub branches: Option<Vec<String>>,
+}
+
+impl Config {
+    /// Load the configuration file at the given path, returning the default configuration when it doesn't exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {path:?}"))?
+        {
+            return Ok(Self::default());
+        }
+
+        toml::from_str(
+            &fs::read_to_string(path)
+                .with_context(|| format!("could not read file at {path:?}"))?,
+        )
+        .with_context(|| format!("invalid configuration file at {path:?}"))
+    }
+}
+
+impl NetworkConfig {
+    /// Get the settings of the network client from these network settings, using the default for each unset option.
+    pub fn client_settings(&self) -> ClientSettings {
+        let mut client_settings = ClientSettings::new();
+        if let Some(connect_timeout) = self.connect_timeout {
+            client_settings =
+                client_settings.with_connect_timeout(Duration::from_secs(connect_timeout));
+        }
+        if let Some(read_timeout) = self.read_timeout {
+            client_settings = client_settings.with_read_timeout(Duration::from_secs(read_timeout));
+        }
+        if let Some(retries) = self.retries {
+            client_settings = client_settings.with_max_retries(retries);
+        }
+        if let Some(proxy) = &self.proxy {
+            client_settings = client_settings.with_proxy(proxy);
+        }
+        if let Some(ca_bundle) = &self.ca_bundle {
+            client_settings = client_settings.with_ca_bundle(ca_bundle);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            client_settings = client_settings.with_user_agent(user_agent);
+        }
+        for (name, value) in &self.headers {
+            client_settings = client_settings.with_header(name, value);
+        }
+        client_settings
+    }
+}
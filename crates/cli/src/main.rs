@@ -1,13 +1,31 @@
 mod commands;
+mod completion;
+mod config;
 mod formatting;
+mod progress;
+mod release_source;
+mod shell;
 
-use self::commands::{Active, Info, Install, List, Remove, RunnableCommand, Update, Use};
+use self::commands::{
+    Active, Cache, Completions, Current, Env, Info, Init, Install, List, Remove, Rollback,
+    RunnableCommand, Shell, Update, Use,
+};
 use crate::formatting::error_text;
-use anyhow::Result;
-use clap::Parser;
-use commands::{SymlinkPath, UpdateAll};
-use nael_core::{dalamud::GoatcorpReleaseSource, fs::storage::CompliantDiskStorage};
-use std::{process::ExitCode, sync::Arc};
+use anyhow::{Context, Result, anyhow};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, parser::ValueSource};
+use clap_complete::CompleteEnv;
+use commands::{SymlinkPath, UpdateAll, Verify};
+use config::Config;
+use nael_core::{
+    dalamud::DalamudInstallation,
+    error::NaelError,
+    fs::storage::{AppStorage, CompliantDiskStorage},
+    net::configure_client,
+    progress::ProgressReporter,
+};
+use progress::ProgressMode;
+use release_source::{AppReleaseSource, GOATCORP_SOURCE_NAME, LOCAL_FILE_SOURCE_NAME};
+use std::{path::PathBuf, process::ExitCode, sync::Arc, time::Duration};
 
 #[cfg(target_os = "windows")]
 use colored::control;
@@ -16,9 +34,19 @@ const APP_QUALIFIER: &str = "dev";
 const APP_ORGANIZATION: &str = "Blooym";
 const APP_NAME: &str = "Nael";
 
+/// The amount of bytes in a mebibyte, which is the unit sizes are configured in.
+const BYTES_PER_MEBIBYTE: u64 = 1024 * 1024;
+
 struct AppState {
     storage: Arc<CompliantDiskStorage>,
-    release_source: GoatcorpReleaseSource,
+    release_source: AppReleaseSource,
+    reporter: Arc<dyn ProgressReporter>,
+    config: Config,
+    /// The name of the release source selected for this invocation.
+    source_name: String,
+    /// Whether the release source was selected with `--source` on the command line, rather than coming from the
+    /// `NAEL_SOURCE` environment variable or the configuration file.
+    source_selected: bool,
 }
 
 #[derive(Debug, Parser)]
@@ -26,12 +54,20 @@ enum NaelCommand {
     Install(Install),
     Update(Update),
     UpdateAll(UpdateAll),
+    Rollback(Rollback),
     Remove(Remove),
     List(List),
     Use(Use),
     Active(Active),
+    Current(Current),
+    Env(Env),
+    Shell(Shell),
+    Init(Init),
+    Completions(Completions),
     SymlinkPath(SymlinkPath),
     Info(Info),
+    Verify(Verify),
+    Cache(Cache),
 }
 
 impl RunnableCommand for NaelCommand {
@@ -41,11 +77,19 @@ impl RunnableCommand for NaelCommand {
             NaelCommand::Remove(cmd) => cmd.run(state).await,
             NaelCommand::Update(cmd) => cmd.run(state).await,
             NaelCommand::UpdateAll(cmd) => cmd.run(state).await,
+            NaelCommand::Rollback(cmd) => cmd.run(state).await,
             NaelCommand::List(cmd) => cmd.run(state).await,
             NaelCommand::Info(cmd) => cmd.run(state).await,
             NaelCommand::Use(cmd) => cmd.run(state).await,
             NaelCommand::Active(cmd) => cmd.run(state).await,
+            NaelCommand::Current(cmd) => cmd.run(state).await,
+            NaelCommand::Env(cmd) => cmd.run(state).await,
+            NaelCommand::Shell(cmd) => cmd.run(state).await,
+            NaelCommand::Init(cmd) => cmd.run(state).await,
+            NaelCommand::Completions(cmd) => cmd.run(state).await,
             NaelCommand::SymlinkPath(cmd) => cmd.run(state).await,
+            NaelCommand::Verify(cmd) => cmd.run(state).await,
+            NaelCommand::Cache(cmd) => cmd.run(state).await,
         }
     }
 }
@@ -55,6 +99,236 @@ impl RunnableCommand for NaelCommand {
 struct Opts {
     #[clap(subcommand)]
     cmd: NaelCommand,
+
+    /// The amount of previous builds to keep for each branch when updating [default: 3].
+    #[clap(
+        long = "history-retention",
+        global = true,
+        env = "NAEL_HISTORY_RETENTION"
+    )]
+    history_retention: Option<usize>,
+
+    /// The maximum total size of cached release archives in mebibytes, 0 disables the cache [default: 512].
+    #[clap(
+        long = "cache-size-limit",
+        global = true,
+        env = "NAEL_CACHE_SIZE_LIMIT"
+    )]
+    cache_size_limit: Option<u64>,
+
+    /// The maximum total uncompressed size of a release archive in mebibytes, larger archives are not extracted [default: 2048].
+    #[clap(
+        long = "extract-size-limit",
+        global = true,
+        env = "NAEL_EXTRACT_SIZE_LIMIT"
+    )]
+    extract_size_limit: Option<u64>,
+
+    /// The maximum amount of entries of a release archive, archives with more are not extracted [default: 100000].
+    #[clap(
+        long = "extract-entry-limit",
+        global = true,
+        env = "NAEL_EXTRACT_ENTRY_LIMIT"
+    )]
+    extract_entry_limit: Option<u64>,
+
+    /// The time in seconds allowed to establish a connection [default: 10].
+    #[clap(long = "connect-timeout", global = true, env = "NAEL_CONNECT_TIMEOUT")]
+    connect_timeout: Option<u64>,
+
+    /// The time in seconds allowed between receiving two pieces of data before a request is considered stalled [default: 30].
+    #[clap(long = "read-timeout", global = true, env = "NAEL_READ_TIMEOUT")]
+    read_timeout: Option<u64>,
+
+    /// The amount of times a failed network request is retried with an increasing delay [default: 3].
+    #[clap(long = "retries", global = true, env = "NAEL_RETRIES")]
+    retries: Option<u32>,
+
+    /// The URL of a proxy to send every network request through, supports http, https, socks5 and socks5h.
+    ///
+    /// When not set, the standard HTTP_PROXY, HTTPS_PROXY and NO_PROXY environment variables are used.
+    #[clap(long = "proxy", global = true, env = "NAEL_PROXY")]
+    proxy: Option<String>,
+
+    /// The path to a file of PEM encoded certificates to trust in addition to the built-in root certificates.
+    #[clap(long = "ca-bundle", global = true, env = "NAEL_CA_BUNDLE")]
+    ca_bundle: Option<PathBuf>,
+
+    /// The user agent to send with every network request.
+    #[clap(long = "user-agent", global = true, env = "NAEL_USER_AGENT")]
+    user_agent: Option<String>,
+
+    /// An additional header to send with every network request in the 'Name: value' format, can be given multiple times.
+    ///
+    /// Multiple headers can be given with the environment variable by separating them with newlines.
+    #[clap(
+        long = "header",
+        value_name = "HEADER",
+        global = true,
+        env = "NAEL_HEADERS",
+        value_delimiter = '\n',
+        value_parser = parse_header
+    )]
+    headers: Vec<(String, String)>,
+
+    /// How download and extraction progress is output to stderr.
+    #[clap(
+        long = "progress",
+        value_name = "MODE",
+        global = true,
+        env = "NAEL_PROGRESS",
+        default_value = "auto"
+    )]
+    progress: ProgressMode,
+
+    /// Don't output download and extraction progress, same as '--progress none'.
+    #[clap(long = "no-progress", global = true)]
+    no_progress: bool,
+
+    /// Don't output progress or warnings.
+    #[clap(short = 'q', long = "quiet", global = true, env = "NAEL_QUIET")]
+    quiet: bool,
+
+    /// The name of the release source to install and update branches from [default: goatcorp].
+    ///
+    /// Either 'goatcorp', 'kamori' or the name of a source from the configuration file.
+    #[clap(long = "source", global = true, env = "NAEL_SOURCE")]
+    source: Option<String>,
+}
+
+impl AppState {
+    /// Build the application state from the command line options and the configuration file.
+    fn new(opts: &Opts, matches: &ArgMatches) -> Result<Self> {
+        let mut storage = CompliantDiskStorage::new(APP_QUALIFIER, APP_ORGANIZATION, APP_NAME);
+        let config = Config::load(&storage.get_config_file_path()?)?;
+
+        if let Some(history_retention) = opts.history_retention.or(config.history_retention) {
+            storage = storage.with_history_retention(history_retention);
+        }
+        if let Some(cache_size_limit) = opts.cache_size_limit.or(config.cache_size_limit) {
+            storage =
+                storage.with_cache_size_limit(cache_size_limit.saturating_mul(BYTES_PER_MEBIBYTE));
+        }
+        if let Some(extract_size_limit) = opts.extract_size_limit.or(config.extract_size_limit) {
+            storage = storage
+                .with_extract_size_limit(extract_size_limit.saturating_mul(BYTES_PER_MEBIBYTE));
+        }
+        if let Some(extract_entry_limit) = opts.extract_entry_limit.or(config.extract_entry_limit) {
+            storage = storage.with_extract_entry_limit(extract_entry_limit);
+        }
+
+        let mut client_settings = config.network.client_settings();
+        if let Some(connect_timeout) = opts.connect_timeout {
+            client_settings =
+                client_settings.with_connect_timeout(Duration::from_secs(connect_timeout));
+        }
+        if let Some(read_timeout) = opts.read_timeout {
+            client_settings = client_settings.with_read_timeout(Duration::from_secs(read_timeout));
+        }
+        if let Some(retries) = opts.retries {
+            client_settings = client_settings.with_max_retries(retries);
+        }
+        if let Some(proxy) = &opts.proxy {
+            client_settings = client_settings.with_proxy(proxy);
+        }
+        if let Some(ca_bundle) = &opts.ca_bundle {
+            client_settings = client_settings.with_ca_bundle(ca_bundle);
+        }
+        if let Some(user_agent) = &opts.user_agent {
+            client_settings = client_settings.with_user_agent(user_agent);
+        }
+        for (name, value) in &opts.headers {
+            client_settings = client_settings.with_header(name, value);
+        }
+        configure_client(client_settings)?;
+
+        let source_name = opts
+            .source
+            .as_deref()
+            .or(config.default_source.as_deref())
+            .unwrap_or(GOATCORP_SOURCE_NAME)
+            .to_owned();
+
+        Ok(Self {
+            release_source: AppReleaseSource::from_name(&source_name, &config)?,
+            source_name,
+            source_selected: matches.value_source("source") == Some(ValueSource::CommandLine),
+            config,
+            storage: Arc::from(storage),
+            reporter: progress::create_reporter(
+                if opts.no_progress {
+                    ProgressMode::None
+                } else {
+                    opts.progress
+                },
+                opts.quiet,
+            ),
+        })
+    }
+
+    /// Get the name of the release source to update or repair the given installation from along with the source itself,
+    /// or [`None`] when it was installed from a local file.
+    ///
+    /// Installations use the release source they were installed from, unless a release source was selected with
+    /// `--source` on the command line or they were installed before release sources were recorded.
+    fn get_installation_source<S: AppStorage>(
+        &self,
+        installation: &DalamudInstallation<S>,
+    ) -> Result<Option<(String, AppReleaseSource)>> {
+        if self.source_selected {
+            return Ok(Some((
+                self.source_name.clone(),
+                self.release_source.clone(),
+            )));
+        }
+        match installation.get_source_name()? {
+            Some(source_name) if source_name == LOCAL_FILE_SOURCE_NAME => Ok(None),
+            Some(source_name) if source_name != self.source_name => {
+                let release_source = AppReleaseSource::from_name(&source_name, &self.config)
+                    .with_context(|| {
+                        format!(
+                            "Unable to use the release source '{}' that branch '{}' was installed from",
+                            source_name, installation.branch_name
+                        )
+                    })?;
+                Ok(Some((source_name, release_source)))
+            }
+            _ => Ok(Some((
+                self.source_name.clone(),
+                self.release_source.clone(),
+            ))),
+        }
+    }
+}
+
+/// Get the exit code for an error from the [`NaelError`] it was caused by, so that scripts can tell common failures apart.
+///
+/// Exit code 2 is used by clap for invalid command line usage and 1 for every failure without a more specific code.
+fn get_exit_code(err: &anyhow::Error) -> ExitCode {
+    let Some(err) = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<NaelError>())
+    else {
+        return ExitCode::FAILURE;
+    };
+    ExitCode::from(match err {
+        NaelError::BranchNotInstalled(_) => 3,
+        NaelError::BranchAlreadyInstalled(_) => 4,
+        NaelError::ReleaseNotFound { .. } | NaelError::HttpStatus { status: 404, .. } => 5,
+        NaelError::Network(_) | NaelError::HttpStatus { .. } => 6,
+        NaelError::PermissionDenied(_) => 7,
+        NaelError::ChecksumMismatch { .. } | NaelError::InvalidArchive(_) => 8,
+        NaelError::BranchPinned { .. } => 9,
+        _ => 1,
+    })
+}
+
+/// Parse a header given on the command line in the `Name: value` format.
+fn parse_header(header: &str) -> Result<(String, String)> {
+    let (name, value) = header
+        .split_once(':')
+        .ok_or_else(|| anyhow!("header '{header}' is not in the 'Name: value' format"))?;
+    Ok((name.trim().to_owned(), value.trim().to_owned()))
 }
 
 #[tokio::main]
@@ -62,22 +336,17 @@ async fn main() -> ExitCode {
     #[cfg(target_os = "windows")]
     control::set_virtual_terminal(true).expect("Failed to set virtual terminal");
 
-    let opts = Opts::parse();
-
-    if let Err(err) = opts
-        .cmd
-        .run(&AppState {
-            release_source: GoatcorpReleaseSource,
-            storage: Arc::from(CompliantDiskStorage::new(
-                APP_QUALIFIER,
-                APP_ORGANIZATION,
-                APP_NAME,
-            )),
-        })
-        .await
-    {
+    CompleteEnv::with_factory(Opts::command).complete();
+    let matches = Opts::command().get_matches();
+    let opts = Opts::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+
+    let result = match AppState::new(&opts, &matches) {
+        Ok(state) => opts.cmd.run(&state).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = result {
         eprintln!("{}: {:?}", error_text("error"), err);
-        return ExitCode::from(1);
+        return get_exit_code(&err);
     };
     ExitCode::SUCCESS
 }
@@ -0,0 +1,251 @@
+use crate::formatting::warning_text;
+use clap::ValueEnum;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use nael_core::progress::{ProgressEvent, ProgressKind, ProgressReporter};
+use std::{
+    collections::HashMap,
+    io::{IsTerminal, Write, stderr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// The least amount of time between two JSON progress updates of the same task.
+const JSON_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How progress is output to stderr.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressMode {
+    /// Progress bars when stderr is a terminal, otherwise plain output.
+    #[default]
+    Auto,
+    /// Interactive progress bars.
+    Bars,
+    /// A line of text when each task starts.
+    Plain,
+    /// Newline-delimited JSON events.
+    Json,
+    /// No progress, only warnings.
+    None,
+}
+
+/// Create the reporter for the given progress mode, which reports nothing at all when quiet.
+pub fn create_reporter(mode: ProgressMode, quiet: bool) -> Arc<dyn ProgressReporter> {
+    if quiet {
+        return Arc::new(QuietReporter);
+    }
+    match mode {
+        ProgressMode::Auto if stderr().is_terminal() => Arc::new(MultiProgressReporter::new()),
+        ProgressMode::Auto | ProgressMode::Plain => Arc::new(PlainReporter { progress: true }),
+        ProgressMode::Bars => Arc::new(MultiProgressReporter::new()),
+        ProgressMode::Json => Arc::new(JsonReporter::default()),
+        ProgressMode::None => Arc::new(PlainReporter { progress: false }),
+    }
+}
+
+/// Output a warning to stderr.
+fn print_warning(message: &str) {
+    eprintln!("{}", warning_text(&format!("Warning: {message}")));
+}
+
+/// A [`ProgressReporter`] that renders every running task as its own line of an [`indicatif::MultiProgress`] on stderr,
+/// so that concurrent downloads and extractions don't interleave.
+#[derive(Debug, Default)]
+struct MultiProgressReporter {
+    multi_progress: MultiProgress,
+    tasks: Mutex<HashMap<u64, BarTask>>,
+}
+
+/// The progress bar of a running task.
+#[derive(Debug)]
+struct BarTask {
+    bar: ProgressBar,
+    description: String,
+    entries: Option<u64>,
+}
+
+impl MultiProgressReporter {
+    /// Create a new reporter without any tasks.
+    fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ProgressReporter for MultiProgressReporter {
+    fn report(&self, event: ProgressEvent) {
+        let mut tasks = self.tasks.lock().unwrap();
+        match event {
+            ProgressEvent::Started {
+                id,
+                kind,
+                name,
+                total,
+                entries,
+            } => {
+                let description = describe_task(kind, &name);
+                let bar = self.multi_progress.add(create_bar(total));
+                bar.set_message(match entries {
+                    Some(entries) => format!("{description} (0/{entries} entries)"),
+                    None => description.clone(),
+                });
+                tasks.insert(
+                    id,
+                    BarTask {
+                        bar,
+                        description,
+                        entries,
+                    },
+                );
+            }
+            ProgressEvent::Progressed { id, position } => {
+                if let Some(task) = tasks.get(&id) {
+                    task.bar.set_position(position);
+                }
+            }
+            ProgressEvent::Entry { id, processed, .. } => {
+                if let Some(task) = tasks.get(&id) {
+                    task.bar.set_message(match task.entries {
+                        Some(entries) => {
+                            format!("{} ({processed}/{entries} entries)", task.description)
+                        }
+                        None => format!("{} ({processed} entries)", task.description),
+                    });
+                }
+            }
+            ProgressEvent::Finished { id, .. } => {
+                if let Some(task) = tasks.remove(&id) {
+                    task.bar.finish_and_clear();
+                    self.multi_progress.remove(&task.bar);
+                }
+            }
+            ProgressEvent::Warning { message } => {
+                self.multi_progress.suspend(|| print_warning(&message));
+            }
+        }
+    }
+}
+
+/// Create a progress bar for a task, falling back to a spinner when its total is unknown.
+fn create_bar(total: Option<u64>) -> ProgressBar {
+    let bar = match total {
+        Some(total) => ProgressBar::new(total).with_style(
+            ProgressStyle::default_bar()
+                .template(
+                    "[{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta}) {msg}",
+                )
+                .unwrap()
+                .progress_chars("#>-"),
+        ),
+        None => ProgressBar::new_spinner().with_style(
+            ProgressStyle::default_spinner()
+                .template("[{elapsed_precise}] {spinner:.green} {bytes} {msg}")
+                .unwrap(),
+        ),
+    };
+    if total.is_none() {
+        bar.enable_steady_tick(Duration::from_millis(100));
+    }
+    bar
+}
+
+/// Get a human readable description of a task.
+fn describe_task(kind: ProgressKind, name: &str) -> String {
+    match kind {
+        ProgressKind::Download => name.to_owned(),
+        ProgressKind::Extract => format!("Extracting archive for '{name}'..."),
+    }
+}
+
+/// A [`ProgressReporter`] that outputs a single line to stderr when a task starts, for logs and other non-interactive
+/// output.
+#[derive(Debug)]
+struct PlainReporter {
+    /// Whether to output the start of tasks, otherwise only warnings are output.
+    progress: bool,
+}
+
+impl ProgressReporter for PlainReporter {
+    fn report(&self, event: ProgressEvent) {
+        match event {
+            ProgressEvent::Started { kind, name, .. } if self.progress => match kind {
+                ProgressKind::Download => eprintln!("Downloading {name}..."),
+                ProgressKind::Extract => eprintln!("{}", describe_task(kind, &name)),
+            },
+            ProgressEvent::Warning { message } => print_warning(&message),
+            _ => {}
+        }
+    }
+}
+
+/// A [`ProgressReporter`] that outputs every event to stderr as a line of JSON, limiting how often progress of a single
+/// task is output.
+#[derive(Debug, Default)]
+struct JsonReporter {
+    /// The output state of each running task.
+    tasks: Mutex<HashMap<u64, JsonTaskState>>,
+}
+
+/// The time and position of the last output progress of a task, along with its latest position.
+#[derive(Debug)]
+struct JsonTaskState {
+    last_output: Instant,
+    output_position: u64,
+    position: u64,
+}
+
+impl JsonReporter {
+    /// Output a single event as a line of JSON.
+    fn output(event: &ProgressEvent) {
+        if let Ok(json) = serde_json::to_string(event) {
+            let _ = writeln!(stderr().lock(), "{json}");
+        }
+    }
+}
+
+impl ProgressReporter for JsonReporter {
+    fn report(&self, event: ProgressEvent) {
+        let mut tasks = self.tasks.lock().unwrap();
+        match &event {
+            ProgressEvent::Started { id, .. } => {
+                tasks.insert(
+                    *id,
+                    JsonTaskState {
+                        last_output: Instant::now(),
+                        output_position: 0,
+                        position: 0,
+                    },
+                );
+            }
+            ProgressEvent::Progressed { id, position } => {
+                if let Some(task) = tasks.get_mut(id) {
+                    task.position = *position;
+                    if task.last_output.elapsed() < JSON_PROGRESS_INTERVAL {
+                        return;
+                    }
+                    task.last_output = Instant::now();
+                    task.output_position = *position;
+                }
+            }
+            ProgressEvent::Finished { id, .. } => {
+                // Make sure the final position is always output before the task finishes.
+                if let Some(task) = tasks.remove(id) {
+                    if task.position != task.output_position {
+                        Self::output(&ProgressEvent::Progressed {
+                            id: *id,
+                            position: task.position,
+                        });
+                    }
+                }
+            }
+            ProgressEvent::Entry { .. } | ProgressEvent::Warning { .. } => {}
+        }
+        Self::output(&event);
+    }
+}
+
+/// A [`ProgressReporter`] that discards everything.
+#[derive(Debug)]
+struct QuietReporter;
+
+impl ProgressReporter for QuietReporter {
+    fn report(&self, _: ProgressEvent) {}
+}
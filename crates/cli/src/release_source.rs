@@ -0,0 +1,127 @@
+use crate::config::{Config, SourceKind};
+use anyhow::{Result, anyhow};
+use nael_core::{
+    dalamud::{
+        GoatcorpReleaseSource, KamoriReleaseSource, ReleaseSource, UrlTemplateReleaseSource,
+        VersionedRelease,
+    },
+    net::RemoteResource,
+};
+
+/// The name of the built-in source for the official dalamud-distrib repository.
+pub const GOATCORP_SOURCE_NAME: &str = "goatcorp";
+
+/// The name of the built-in source for the release metadata used by the official launchers.
+pub const KAMORI_SOURCE_NAME: &str = "kamori";
+
+/// The name recorded for branches installed from a local release archive, which can't be selected as a release source.
+pub const LOCAL_FILE_SOURCE_NAME: &str = "file";
+
+/// The release source selected for this invocation.
+#[derive(Debug, Clone)]
+pub enum AppReleaseSource {
+    Goatcorp(GoatcorpReleaseSource),
+    Kamori(KamoriReleaseSource),
+    UrlTemplate(UrlTemplateReleaseSource),
+}
+
+impl AppReleaseSource {
+    /// Get the release source with the given name, either a built-in source or a named source from the configuration.
+    pub fn from_name(name: &str, config: &Config) -> Result<Self> {
+        if name == LOCAL_FILE_SOURCE_NAME {
+            return Err(anyhow!(
+                "The release source name '{}' is reserved for branches installed from a local file",
+                LOCAL_FILE_SOURCE_NAME
+            ));
+        }
+        if let Some(source) = config.sources.get(name) {
+            return Ok(match source.kind {
+                SourceKind::Kamori => Self::Kamori(KamoriReleaseSource::new(&source.base_url)),
+                SourceKind::Distrib => {
+                    let mut release_source = UrlTemplateReleaseSource::new(&source.base_url);
+                    if let Some(root_branch_name) = &source.root_branch_name {
+                        release_source = release_source.with_root_branch_name(root_branch_name);
+                    }
+                    if let Some(archive_filename) = &source.archive_filename {
+                        release_source =
+                            release_source.with_release_archive_filename(archive_filename);
+                    }
+                    if let Some(version_filename) = &source.version_filename {
+                        release_source =
+                            release_source.with_version_info_filename(version_filename);
+                    }
+                    if let Some(versions_directory) = &source.versions_directory {
+                        release_source = release_source.with_versions_directory(versions_directory);
+                    }
+                    if let Some(checksum_filename) = &source.checksum_filename {
+                        release_source = release_source.with_checksum_filename(checksum_filename);
+                    }
+                    if let Some(branches) = &source.branches {
+                        release_source = release_source.with_branch_names(branches);
+                    }
+                    Self::UrlTemplate(release_source)
+                }
+            });
+        }
+
+        match name {
+            GOATCORP_SOURCE_NAME => Ok(Self::Goatcorp(GoatcorpReleaseSource)),
+            KAMORI_SOURCE_NAME => Ok(Self::Kamori(KamoriReleaseSource::default())),
+            _ => Err(anyhow!(
+                "Unknown release source '{}', available sources are: {}",
+                name,
+                [GOATCORP_SOURCE_NAME, KAMORI_SOURCE_NAME]
+                    .into_iter()
+                    .chain(config.sources.keys().map(String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+}
+
+impl ReleaseSource for AppReleaseSource {
+    async fn get_release_archive_file(&self, branch: &str) -> Result<RemoteResource> {
+        match self {
+            Self::Goatcorp(source) => source.get_release_archive_file(branch).await,
+            Self::Kamori(source) => source.get_release_archive_file(branch).await,
+            Self::UrlTemplate(source) => source.get_release_archive_file(branch).await,
+        }
+    }
+
+    async fn get_version_info_file(&self, branch: &str) -> Result<RemoteResource> {
+        match self {
+            Self::Goatcorp(source) => source.get_version_info_file(branch).await,
+            Self::Kamori(source) => source.get_version_info_file(branch).await,
+            Self::UrlTemplate(source) => source.get_version_info_file(branch).await,
+        }
+    }
+
+    async fn get_checksum_file(&self, branch: &str) -> Result<Option<RemoteResource>> {
+        match self {
+            Self::Goatcorp(source) => source.get_checksum_file(branch).await,
+            Self::Kamori(source) => source.get_checksum_file(branch).await,
+            Self::UrlTemplate(source) => source.get_checksum_file(branch).await,
+        }
+    }
+
+    async fn get_versioned_release(
+        &self,
+        branch: &str,
+        version: &str,
+    ) -> Result<Option<VersionedRelease>> {
+        match self {
+            Self::Goatcorp(source) => source.get_versioned_release(branch, version).await,
+            Self::Kamori(source) => source.get_versioned_release(branch, version).await,
+            Self::UrlTemplate(source) => source.get_versioned_release(branch, version).await,
+        }
+    }
+
+    async fn get_branches(&self) -> Result<Vec<String>> {
+        match self {
+            Self::Goatcorp(source) => source.get_branches().await,
+            Self::Kamori(source) => source.get_branches().await,
+            Self::UrlTemplate(source) => source.get_branches().await,
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use clap_complete::env::Shells;
+use std::{env, io::Write, path::Path};
+
+/// The environment variable that tells plugin builds where Dalamud is located.
+pub const DALAMUD_HOME_ENV: &str = "DALAMUD_HOME";
+
+/// The environment variable that makes nael output completions for the shell it is set to instead of running a command.
+const COMPLETE_ENV: &str = "COMPLETE";
+
+/// The placeholder inside of the hooks that is replaced with the command that points DALAMUD_HOME at the symlink of the
+/// active branch.
+const HOOK_FALLBACK_PLACEHOLDER: &str = "__NAEL_SET_FALLBACK__";
+
+/// The Bash hook that updates DALAMUD_HOME from project files whenever the current directory changes.
+const BASH_AUTO_SWITCH_HOOK: &str = r#"_nael_hook() {
+  if [ "${_NAEL_LAST_PWD-}" != "$PWD" ]; then
+    _NAEL_LAST_PWD="$PWD"
+    local nael_env
+    if nael_env="$(command nael env --shell bash)"; then
+      eval "$nael_env"
+    else
+      __NAEL_SET_FALLBACK__
+    fi
+  fi
+}
+if [[ ";${PROMPT_COMMAND-};" != *";_nael_hook;"* ]]; then
+  PROMPT_COMMAND="_nael_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
+"#;
+
+/// The Zsh hook that updates DALAMUD_HOME from project files whenever the current directory changes.
+const ZSH_AUTO_SWITCH_HOOK: &str = r#"_nael_hook() {
+  local nael_env
+  if nael_env="$(command nael env --shell zsh)"; then
+    eval "$nael_env"
+  else
+    __NAEL_SET_FALLBACK__
+  fi
+}
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd _nael_hook
+_nael_hook
+"#;
+
+/// The Fish hook that updates DALAMUD_HOME from project files whenever the current directory changes.
+const FISH_AUTO_SWITCH_HOOK: &str = r#"function _nael_hook --on-variable PWD
+    if set -l nael_env (command nael env --shell fish)
+        printf '%s\n' $nael_env | source
+    else
+        __NAEL_SET_FALLBACK__
+    end
+end
+_nael_hook
+"#;
+
+/// The PowerShell hook that updates DALAMUD_HOME from project files whenever the current directory changes.
+const POWERSHELL_AUTO_SWITCH_HOOK: &str = r#"function global:_NaelHook {
+    $naelEnv = nael env --shell powershell
+    if ($LASTEXITCODE -eq 0) {
+        $naelEnv | Out-String | Invoke-Expression
+    } else {
+        __NAEL_SET_FALLBACK__
+    }
+}
+if (-not $global:_NaelPreviousPrompt) {
+    $global:_NaelPreviousPrompt = $function:prompt
+    function global:prompt {
+        if ($global:_NaelLastLocation -ne $PWD.Path) {
+            $global:_NaelLastLocation = $PWD.Path
+            _NaelHook
+        }
+        & $global:_NaelPreviousPrompt
+    }
+}
+"#;
+
+/// A shell that nael can output commands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+    #[clap(name = "powershell")]
+    PowerShell,
+}
+
+impl ShellKind {
+    /// Detect the shell of the current user from the `SHELL` environment variable, falling back to PowerShell on
+    /// Windows and Bash everywhere else.
+    pub fn detect() -> Self {
+        let shell = env::var_os("SHELL");
+        match shell
+            .as_deref()
+            .and_then(|shell| Path::new(shell).file_stem())
+            .and_then(|name| name.to_str())
+        {
+            Some("zsh") => Self::Zsh,
+            Some("fish") => Self::Fish,
+            Some("pwsh" | "powershell") => Self::PowerShell,
+            Some(_) => Self::Bash,
+            None if cfg!(windows) => Self::PowerShell,
+            None => Self::Bash,
+        }
+    }
+
+    /// Get the command that sets an environment variable to the given value in this shell.
+    pub fn set_env_command(self, name: &str, value: &str) -> String {
+        match self {
+            Self::Bash | Self::Zsh => format!("export {name}='{}'", value.replace('\'', r"'\''")),
+            Self::Fish => format!(
+                "set -gx {name} '{}'",
+                value.replace('\\', r"\\").replace('\'', r"\'")
+            ),
+            Self::PowerShell => format!("$env:{name} = '{}'", value.replace('\'', "''")),
+        }
+    }
+
+    /// Get the hook that keeps DALAMUD_HOME up to date whenever the current directory changes, by using the branch from
+    /// the nearest project file or the given symlink of the active branch when there is none.
+    pub fn auto_switch_hook(self, active_branch_symlink: &str) -> String {
+        let hook = match self {
+            Self::Bash => BASH_AUTO_SWITCH_HOOK,
+            Self::Zsh => ZSH_AUTO_SWITCH_HOOK,
+            Self::Fish => FISH_AUTO_SWITCH_HOOK,
+            Self::PowerShell => POWERSHELL_AUTO_SWITCH_HOOK,
+        };
+        hook.replace(
+            HOOK_FALLBACK_PLACEHOLDER,
+            &self.set_env_command(DALAMUD_HOME_ENV, active_branch_symlink),
+        )
+    }
+
+    /// Write the script that registers completions for the given binary with this shell.
+    ///
+    /// The script calls back into the binary whenever completions are requested, so dynamic values such as branch names
+    /// are always up to date.
+    pub fn write_completions(self, bin: &str, buf: &mut dyn Write) -> Result<()> {
+        let name = match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+            Self::PowerShell => "powershell",
+        };
+        Shells::builtins()
+            .completer(name)
+            .with_context(|| format!("no completions are available for {name}"))?
+            .write_registration(COMPLETE_ENV, bin, bin, bin, buf)
+            .context("failed to write completions")
+    }
+}
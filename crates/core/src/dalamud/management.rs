@@ -1,14 +1,77 @@
-use super::{sources::ReleaseSource, version_info::DalamudVersionInfo};
-use crate::fs::{archive, storage::AppStorage};
+use super::{
+    sources::{ReleaseSource, VersionedRelease},
+    version_info::DalamudVersionInfo,
+};
+use crate::{
+    error::NaelError,
+    fs::{
+        archive::{self, ExtractLimits},
+        cache::ArchiveCache,
+        checksum,
+        manifest::{Manifest, ManifestDiff},
+        storage::AppStorage,
+    },
+    net::RemoteResource,
+    progress::ProgressReporter,
+};
 use anyhow::{Context, Result, anyhow};
 use serde::de::IgnoredAny;
 use std::{
     fmt::Debug,
     fs::{self},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
 };
-use tempfile::tempdir;
+
+/// The separator between a branch name and a pinned version inside of an installation name (e.g. `stg@9.0.0.17`).
+const PINNED_VERSION_SEPARATOR: char = '@';
+
+/// Split an installation name into the name of the remote branch it tracks and the version it is pinned to, if any.
+///
+/// For example, `stg@9.0.0.17` is split into `("stg", Some("9.0.0.17"))` and `stg` into `("stg", None)`.
+pub fn split_installation_name(name: &str) -> (&str, Option<&str>) {
+    match name.split_once(PINNED_VERSION_SEPARATOR) {
+        Some((branch, version)) => (branch, Some(version)),
+        None => (name, None),
+    }
+}
+
+/// Check that an installation name can be safely used as the name of a directory inside of the branches directory.
+///
+/// Both the branch name and pinned version must be non-empty, must not contain `/`, `\` or `..` and must not start with
+/// a `.`, so that names read from untrusted sources such as project files can't point outside of the branches directory.
+///
+/// # Errors
+/// This function will return an error in the following situations, but is not limited to just these cases:
+/// * When either part of the installation name is not valid ([`NaelError::InvalidBranchName`]).
+pub fn validate_installation_name(name: &str) -> Result<(), NaelError> {
+    let (branch, version) = split_installation_name(name);
+    let is_valid = |part: &str| {
+        !part.is_empty()
+            && !part.starts_with('.')
+            && !part.contains(['/', '\\'])
+            && !part.contains("..")
+    };
+    if !is_valid(branch) || version.is_some_and(|version| !is_valid(version)) {
+        return Err(NaelError::InvalidBranchName(name.to_owned()));
+    }
+    Ok(())
+}
+
+/// A release archive on disk that is ready to be installed.
+struct ReleaseArchive {
+    /// The path of the release archive.
+    path: PathBuf,
+    /// Whether the release archive was downloaded, in which case it is moved into the cache once verified.
+    downloaded: bool,
+    /// The checksum the release archive is expected to have, if known.
+    expected_checksum: Option<String>,
+    /// The raw version information of the release, if known.
+    version_info: Option<String>,
+    /// The name of the release source the release archive was obtained from, if known.
+    source_name: Option<String>,
+}
 
 /// Information about a Dalamud branch install & utilities for managing all installations.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -22,51 +85,266 @@ pub struct DalamudInstallation<S: AppStorage> {
 
 impl<S: AppStorage> DalamudInstallation<S> {
     /// Downloads & extracts the remote branch & version information to [`AppStorage::get_branch_directory`].
-    ///
-    /// # Notes
-    /// This function will attempt to prevent cases where a local installation is deleted when a remote version download fails.
-    /// If there is already an installation of the given branch locally it will be overwritten only when the archive is being extracted.
     async fn download_branch_impl<RS: ReleaseSource>(
         branch_name: &str,
         storage: &Arc<S>,
         release_source: &RS,
+        source_name: &str,
+        reporter: &Arc<dyn ProgressReporter>,
+    ) -> Result<DalamudInstallation<S>> {
+        let release = Self::locate_release(branch_name, release_source).await?;
+        Self::install_release_impl(
+            branch_name,
+            storage,
+            &release,
+            Some(source_name),
+            true,
+            reporter,
+        )
+        .await
+    }
+
+    /// Downloads & extracts the given release assets to [`AppStorage::get_branch_directory`].
+    ///
+    /// # Notes
+    /// The release is extracted into a staging directory next to the branch directory and only swapped into place once
+    /// extraction has fully succeeded, so a failed or interrupted update leaves the existing installation untouched.
+    /// If there is already an installation of the given branch locally it will be moved into the branch history when
+    /// `keep_previous` is set, otherwise it is discarded.
+    async fn install_release_impl(
+        branch_name: &str,
+        storage: &Arc<S>,
+        release: &VersionedRelease,
+        source_name: Option<&str>,
+        keep_previous: bool,
+        reporter: &Arc<dyn ProgressReporter>,
     ) -> Result<DalamudInstallation<S>> {
         let branch_directory = storage.get_branch_directory(branch_name)?;
-        let version_info_path = storage.get_branch_version_info_path(branch_name)?;
+        Self::recover_interrupted_swap(
+            &branch_directory,
+            &branch_directory.with_file_name(format!(".{branch_name}.previous")),
+        )?;
 
-        // Download release archive.
-        let work_dir = tempdir().context("creation temporary working directory failed")?;
-        let download_path = work_dir.path().join("dalamud.zip");
-        release_source
-            .get_release_archive_file(branch_name)
-            .download_with_progress_bar(&download_path)
-            .await
-            .context("release archive download failure")?;
+        // Download version information.
+        let version_info = match &release.version_info_file {
+            Some(version_info_file) => version_info_file.read_to_string().await,
+            None => Err(anyhow!(
+                "the release source did not provide version information"
+            )),
+        };
+        let version_info = match version_info {
+            Ok(version_info) => Some(version_info),
+            Err(err) => {
+                reporter.warn(&format!(
+                    "Unable to obtain version information, this branch will not be able to compare its version against the release source later: {err:#}"
+                ));
+                None
+            }
+        };
 
-        // Extract release archive - delete existing install if found.
-        if branch_directory
+        // Work out which release archive is expected before downloading, so that a cached copy can be used instead.
+        let expected_checksum = match &release.checksum_file {
+            Some(checksum_file) => Some(checksum::parse_sha256(
+                &checksum_file
+                    .read_to_string()
+                    .await
+                    .context("failed to obtain published release archive checksum")?,
+            )?),
+            None => Self::get_recorded_checksum(branch_name, storage, version_info.as_deref())?,
+        };
+        let cache = ArchiveCache::new(
+            &storage.get_cache_directory()?,
+            storage.get_cache_size_limit(),
+        );
+        let cached_archive = Self::find_cached_archive(
+            &cache,
+            expected_checksum.as_deref(),
+            version_info.as_deref(),
+        )
+        .unwrap_or_else(|err| {
+            reporter.warn(&format!(
+                "Unable to read the release archive cache: {err:#}"
+            ));
+            None
+        });
+
+        // Download release archive, unless it is already cached. Downloads are kept in the cache directory until they
+        // complete so that an interrupted download can be resumed by the next attempt.
+        let (archive_path, downloaded) = match cached_archive {
+            Some(cached_archive) => (cached_archive, false),
+            None => {
+                let download_path = cache.get_download_path(&release.archive_file.url)?;
+                release
+                    .archive_file
+                    .download_with_progress(&download_path, reporter.as_ref())
+                    .await
+                    .context("release archive download failure")?;
+                (download_path, true)
+            }
+        };
+
+        Self::install_archive(
+            branch_name,
+            storage,
+            ReleaseArchive {
+                path: archive_path,
+                downloaded,
+                expected_checksum,
+                version_info,
+                source_name: source_name.map(str::to_owned),
+            },
+            keep_previous,
+            reporter,
+        )
+        .await
+    }
+
+    /// Install the release archive at the given path with [`DalamudInstallation::install_archive_blocking`].
+    ///
+    /// Verifying, extracting and swapping in the release are blocking filesystem operations, so they run on the blocking
+    /// thread pool instead of holding up other tasks such as downloads running at the same time.
+    async fn install_archive(
+        branch_name: &str,
+        storage: &Arc<S>,
+        archive: ReleaseArchive,
+        keep_previous: bool,
+        reporter: &Arc<dyn ProgressReporter>,
+    ) -> Result<DalamudInstallation<S>> {
+        let branch_name = branch_name.to_owned();
+        let storage = Arc::clone(storage);
+        let reporter = Arc::clone(reporter);
+        tokio::task::spawn_blocking(move || {
+            Self::install_archive_blocking(
+                &branch_name,
+                &storage,
+                &archive,
+                keep_previous,
+                reporter.as_ref(),
+            )
+        })
+        .await
+        .context("release installation task failed")?
+    }
+
+    /// Verify the release archive at the given path, then extract it into the staging directory of the branch and swap
+    /// it into place.
+    ///
+    /// An archive that was downloaded is stored in the cache once verified and removed from its download path.
+    fn install_archive_blocking(
+        branch_name: &str,
+        storage: &Arc<S>,
+        archive: &ReleaseArchive,
+        keep_previous: bool,
+        reporter: &dyn ProgressReporter,
+    ) -> Result<DalamudInstallation<S>> {
+        let ReleaseArchive {
+            path: archive_path,
+            downloaded,
+            expected_checksum,
+            version_info,
+            ..
+        } = archive;
+        let version_info = version_info.as_deref();
+        let branch_directory = storage.get_branch_directory(branch_name)?;
+        let staging_directory = branch_directory.with_file_name(format!(".{branch_name}.staging"));
+        let displaced_directory =
+            branch_directory.with_file_name(format!(".{branch_name}.previous"));
+        let cache = ArchiveCache::new(
+            &storage.get_cache_directory()?,
+            storage.get_cache_size_limit(),
+        );
+
+        // Verify the release archive against the published checksum, or the one recorded when this version was last installed.
+        let checksum = checksum::sha256_file(&archive_path)
+            .context("failed to calculate release archive checksum")?;
+        if let Some(expected_checksum) = expected_checksum {
+            if &checksum != expected_checksum {
+                if *downloaded {
+                    let _ = fs::remove_file(archive_path);
+                }
+                return Err(NaelError::ChecksumMismatch {
+                    expected: expected_checksum.clone(),
+                    actual: checksum,
+                }
+                .into());
+            }
+        }
+        if *downloaded {
+            let version_info = version_info.and_then(|raw| raw.parse::<DalamudVersionInfo>().ok());
+            if let Err(err) = cache.insert(
+                &archive_path,
+                &checksum,
+                version_info.as_ref().map(|v| v.assembly_version.as_str()),
+                version_info.as_ref().and_then(|v| v.git_sha.as_deref()),
+            ) {
+                reporter.warn(&format!(
+                    "Unable to store the release archive in the cache: {err:#}"
+                ));
+            }
+        }
+
+        // Extract release archive & version information into the staging directory.
+        if staging_directory
             .try_exists()
-            .with_context(|| format!("unable to check existence of {branch_directory:?}"))?
+            .with_context(|| format!("unable to check existence of {staging_directory:?}"))?
         {
-            fs::remove_dir_all(&branch_directory).with_context(|| {
-                format!("failed to delete existing branch directory {branch_directory:?}",)
+            fs::remove_dir_all(&staging_directory).with_context(|| {
+                format!("failed to remove leftover staging directory {staging_directory:?}")
             })?;
         }
-        archive::extract_with_progress_bar(&download_path, &branch_directory)
-            .context("failed to extract release archive to disk")?;
-        drop(work_dir); // Deletes the temporary directory.
+        let result = Self::stage_release(
+            branch_name,
+            archive,
+            &staging_directory,
+            &checksum,
+            storage,
+            reporter,
+        );
+        if *downloaded {
+            let _ = fs::remove_file(archive_path);
+        }
+        if let Err(err) = result {
+            let _ = fs::remove_dir_all(&staging_directory);
+            return Err(err);
+        }
 
-        // Download version information.
-        if let Err(err) = release_source
-            .get_version_info_file(branch_name)
-            .download_with_progress_bar(&version_info_path)
-            .await
-        {
-            eprintln!("Warning: Unable to obtain version information: {err:?}");
-            eprintln!(
-                "This branch will not be able to compare its version against the release source later."
-            );
-        };
+        // Swap the staged release into place, restoring the existing install if anything goes wrong.
+        let has_existing_install = branch_directory
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {branch_directory:?}"))?;
+        if has_existing_install {
+            if let Err(err) = fs::rename(&branch_directory, &displaced_directory) {
+                let _ = fs::remove_dir_all(&staging_directory);
+                return Err(err).with_context(|| {
+                    format!("failed to move {branch_directory:?} to {displaced_directory:?}")
+                });
+            }
+        }
+        if let Err(err) = fs::rename(&staging_directory, &branch_directory) {
+            if has_existing_install {
+                fs::rename(&displaced_directory, &branch_directory).with_context(|| {
+                    format!("failed to restore {branch_directory:?} after a failed update")
+                })?;
+            }
+            let _ = fs::remove_dir_all(&staging_directory);
+            return Err(err).with_context(|| {
+                format!("failed to move {staging_directory:?} to {branch_directory:?}")
+            });
+        }
+
+        // Keep or discard the previous build - the update has already succeeded at this point so failures are not fatal.
+        if has_existing_install {
+            let result = if keep_previous {
+                Self::move_to_history(branch_name, &displaced_directory, storage)
+            } else {
+                fs::remove_dir_all(&displaced_directory).with_context(|| {
+                    format!("failed to remove previous build {displaced_directory:?}")
+                })
+            };
+            if let Err(err) = result {
+                reporter.warn(&format!("Unable to clean up the previous build: {err:#}"));
+            }
+        }
 
         Ok(DalamudInstallation {
             storage: Arc::clone(storage),
@@ -74,30 +352,271 @@ impl<S: AppStorage> DalamudInstallation<S> {
         })
     }
 
+    /// Locate the release assets of the given branch - pinned installations use the assets of their specific version.
+    async fn locate_release<RS: ReleaseSource>(
+        branch_name: &str,
+        release_source: &RS,
+    ) -> Result<VersionedRelease> {
+        match split_installation_name(branch_name) {
+            (remote_branch, Some(version)) => release_source
+                .get_versioned_release(remote_branch, version)
+                .await
+                .with_context(|| {
+                    format!("failed to look up version {version} of branch {remote_branch}")
+                })?
+                .ok_or_else(|| {
+                    NaelError::ReleaseNotFound {
+                        branch: remote_branch.to_owned(),
+                        version: Some(version.to_owned()),
+                    }
+                    .into()
+                }),
+            (remote_branch, None) => Ok(VersionedRelease {
+                archive_file: release_source
+                    .get_release_archive_file(remote_branch)
+                    .await
+                    .with_context(|| {
+                        format!("failed to locate release archive of {remote_branch}")
+                    })?,
+                // Version information is optional, missing information is warned about when installing.
+                version_info_file: release_source
+                    .get_version_info_file(remote_branch)
+                    .await
+                    .ok(),
+                checksum_file: release_source
+                    .get_checksum_file(remote_branch)
+                    .await
+                    .with_context(|| format!("failed to locate checksum of {remote_branch}"))?,
+            }),
+        }
+    }
+
+    /// Find a cached copy of the expected release archive, either by its checksum or by the version of the release.
+    fn find_cached_archive(
+        cache: &ArchiveCache,
+        expected_checksum: Option<&str>,
+        version_info: Option<&str>,
+    ) -> Result<Option<PathBuf>> {
+        if let Some(expected_checksum) = expected_checksum {
+            return cache.find_by_checksum(expected_checksum);
+        }
+        match version_info.and_then(|raw| raw.parse::<DalamudVersionInfo>().ok()) {
+            Some(version_info) => cache.find_by_version(
+                &version_info.assembly_version,
+                version_info.git_sha.as_deref(),
+            ),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the checksum recorded by the existing installation of the given branch, but only when it is the same release
+    /// as the given remote version information.
+    fn get_recorded_checksum(
+        branch_name: &str,
+        storage: &Arc<S>,
+        remote_version_info: Option<&str>,
+    ) -> Result<Option<String>> {
+        let Some(remote_version_info) =
+            remote_version_info.and_then(|raw| raw.parse::<DalamudVersionInfo>().ok())
+        else {
+            return Ok(None);
+        };
+
+        let version_info_path = storage.get_branch_version_info_path(branch_name)?;
+        let checksum_path = storage.get_branch_checksum_path(branch_name)?;
+        if !version_info_path
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {version_info_path:?}"))?
+            || !checksum_path
+                .try_exists()
+                .with_context(|| format!("unable to check existence of {checksum_path:?}"))?
+        {
+            return Ok(None);
+        }
+
+        if DalamudVersionInfo::from_path_ref(&version_info_path).ok() != Some(remote_version_info) {
+            return Ok(None);
+        }
+
+        Ok(Some(checksum::parse_sha256(
+            &fs::read_to_string(&checksum_path)
+                .with_context(|| format!("could not read file at {checksum_path:?}"))?,
+        )?))
+    }
+
+    /// Extract the release archive to the staging directory, verify the extracted release & write its manifest, version
+    /// information, checksum and release source name alongside it.
+    fn stage_release(
+        branch_name: &str,
+        archive: &ReleaseArchive,
+        staging_directory: &Path,
+        checksum: &str,
+        storage: &Arc<S>,
+        reporter: &dyn ProgressReporter,
+    ) -> Result<()> {
+        archive::extract_with_progress(
+            &archive.path,
+            &staging_directory,
+            branch_name,
+            ExtractLimits {
+                max_size: storage.get_extract_size_limit(),
+                max_entries: storage.get_extract_entry_limit(),
+            },
+            reporter,
+        )
+        .context("failed to extract release archive to disk")?;
+
+        if fs::read_dir(staging_directory)
+            .with_context(|| format!("failed to read {staging_directory:?}"))?
+            .next()
+            .is_none()
+        {
+            return Err(anyhow!("extracted release archive contained no files"));
+        }
+
+        Manifest::from_directory(&staging_directory, &[])
+            .context("failed to create manifest of extracted release")?
+            .write_to_path(&staging_directory.join(storage.get_manifest_filename()))?;
+
+        if let Some(version_info) = &archive.version_info {
+            let version_info_path = staging_directory.join(storage.get_version_info_filename());
+            fs::write(&version_info_path, version_info)
+                .with_context(|| format!("failed to write {version_info_path:?}"))?;
+        }
+
+        let checksum_path = staging_directory.join(storage.get_checksum_filename());
+        fs::write(&checksum_path, checksum)
+            .with_context(|| format!("failed to write {checksum_path:?}"))?;
+
+        if let Some(source_name) = &archive.source_name {
+            let source_path = staging_directory.join(storage.get_source_filename());
+            fs::write(&source_path, source_name)
+                .with_context(|| format!("failed to write {source_path:?}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Restore the branch directory when a previous update was interrupted after moving the existing install aside,
+    /// otherwise clean up the leftover displaced directory.
+    fn recover_interrupted_swap(branch_directory: &Path, displaced_directory: &Path) -> Result<()> {
+        if !displaced_directory
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {displaced_directory:?}"))?
+        {
+            return Ok(());
+        }
+
+        if branch_directory
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {branch_directory:?}"))?
+        {
+            fs::remove_dir_all(displaced_directory).with_context(|| {
+                format!("failed to remove leftover directory {displaced_directory:?}")
+            })
+        } else {
+            fs::rename(displaced_directory, branch_directory).with_context(|| {
+                format!("failed to restore {branch_directory:?} from an interrupted update")
+            })
+        }
+    }
+
+    /// Move a previous build of the given branch into its history directory, pruning any previous builds that exceed
+    /// [`AppStorage::get_history_retention`].
+    fn move_to_history(branch_name: &str, previous_build: &Path, storage: &Arc<S>) -> Result<()> {
+        if storage.get_history_retention() == 0 {
+            fs::remove_dir_all(previous_build).with_context(|| {
+                format!("failed to delete previous build directory {previous_build:?}")
+            })?;
+            return Ok(());
+        }
+
+        let history_directory = storage.get_branch_history_directory(branch_name)?;
+        fs::create_dir_all(&history_directory)
+            .with_context(|| format!("failed to create history directory {history_directory:?}"))?;
+        let history_entry = history_directory.join(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)?
+                .as_millis()
+                .to_string(),
+        );
+        fs::rename(previous_build, &history_entry)
+            .with_context(|| format!("failed to move {previous_build:?} to {history_entry:?}"))?;
+
+        for expired_entry in Self::get_history_entries(branch_name, storage)?
+            .iter()
+            .skip(storage.get_history_retention())
+        {
+            fs::remove_dir_all(expired_entry).with_context(|| {
+                format!("failed to remove expired history entry {expired_entry:?}")
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the previous builds kept in the history directory of the given branch, ordered from newest to oldest.
+    fn get_history_entries(branch_name: &str, storage: &Arc<S>) -> Result<Vec<PathBuf>> {
+        let history_directory = storage.get_branch_history_directory(branch_name)?;
+        if !history_directory
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {history_directory:?}"))?
+        {
+            return Ok(vec![]);
+        }
+
+        let mut entries = fs::read_dir(&history_directory)
+            .with_context(|| format!("failed to read {history_directory:?}"))?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let timestamp = path.file_name()?.to_str()?.parse::<u128>().ok()?;
+                Some((timestamp, path))
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        Ok(entries.into_iter().map(|(_, path)| path).collect())
+    }
+
     /// Create a new branch installation with the given storage.
     ///
+    /// The branch name may be suffixed with `@<version>` to install a specific version of the branch instead of the latest one,
+    /// the version is resolved using [`ReleaseSource::get_versioned_release`]. Pinned installations are stored separately
+    /// to installations of the branch itself, so multiple versions of a branch can be installed side-by-side. Download and
+    /// extraction progress, along with any warnings, is sent to the given reporter.
+    ///
+    /// The given source name is recorded with the installation, see [`DalamudInstallation::get_source_name`].
+    ///
     /// # Errors
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When any [`AppStorage`] operation fails.
-    /// * When the branch already exists locally.
+    /// * When the branch already exists locally ([`NaelError::BranchAlreadyInstalled`]).
+    /// * When the branch name or its pinned version is not valid ([`NaelError::InvalidBranchName`]), see
+    ///   [`validate_installation_name`].
+    /// * When the pinned version cannot be found in the release source ([`NaelError::ReleaseNotFound`]).
     /// * When unable to check if the given branch directory exists.
     /// * When any part of the installation process fails.
+    /// * When the release archive doesn't match its checksum ([`NaelError::ChecksumMismatch`]) or fails validation
+    ///   ([`NaelError::InvalidArchive`]).
     pub async fn create<RS: ReleaseSource>(
         branch_name: &str,
         storage: &Arc<S>,
         release_source: &RS,
-    ) -> Result<DalamudInstallation<S>> {
+        source_name: &str,
+        reporter: &Arc<dyn ProgressReporter>,
+    ) -> Result<DalamudInstallation<S>, NaelError> {
+        validate_installation_name(branch_name)?;
         let branch_directory = storage.get_branch_directory(branch_name)?;
         if branch_directory
             .try_exists()
             .with_context(|| format!("unable to check existence of {branch_directory:?}"))?
         {
-            return Err(anyhow!(
-                "branch {} already exists in storage, obtain an instance and call the update method instead",
-                branch_name,
-            ));
+            return Err(NaelError::BranchAlreadyInstalled(branch_name.to_owned()));
         }
-        Self::download_branch_impl(branch_name, storage, release_source).await
+        Ok(
+            Self::download_branch_impl(branch_name, storage, release_source, source_name, reporter)
+                .await?,
+        )
     }
 
     /// Remove the branch installation from storage.
@@ -108,17 +627,14 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When any [`AppStorage`] operation fails.
     /// * When unable to check if the given branch directory exists.
-    /// * When the branch directory cannot be found.
-    pub fn remove(self) -> Result<()> {
+    /// * When the branch directory cannot be found ([`NaelError::BranchNotInstalled`]).
+    pub fn remove(self) -> Result<(), NaelError> {
         let branch_directory = self.storage.get_branch_directory(&self.branch_name)?;
         if !branch_directory
             .try_exists()
             .with_context(|| format!("unable to check existence of {branch_directory:?}"))?
         {
-            return Err(anyhow!(
-                "unable to find branch {} in versions directory",
-                &self.branch_name
-            ));
+            return Err(NaelError::BranchNotInstalled(self.branch_name));
         }
 
         if self
@@ -132,21 +648,53 @@ impl<S: AppStorage> DalamudInstallation<S> {
         fs::remove_dir_all(&branch_directory)
             .with_context(|| format!("failed to remove branch directory {branch_directory:?}"))?;
 
+        let history_directory = self
+            .storage
+            .get_branch_history_directory(&self.branch_name)?;
+        if history_directory
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {history_directory:?}"))?
+        {
+            fs::remove_dir_all(&history_directory).with_context(|| {
+                format!("failed to remove branch history directory {history_directory:?}")
+            })?;
+        }
+
+        let remote_version_info_cache_path = self
+            .storage
+            .get_branch_remote_version_info_cache_path(&self.branch_name)?;
+        if remote_version_info_cache_path
+            .try_exists()
+            .with_context(|| {
+                format!("unable to check existence of {remote_version_info_cache_path:?}")
+            })?
+        {
+            fs::remove_file(&remote_version_info_cache_path)
+                .with_context(|| format!("failed to remove {remote_version_info_cache_path:?}"))?;
+        }
+
         Ok(())
     }
 
     /// Update the branch installation to the latest remote version, consuming the instance and returning a new one when [`Ok`].
     ///
+    /// The previous build is kept in the branch history according to [`AppStorage::get_history_retention`] and can be
+    /// restored with [`DalamudInstallation::rollback`]. Progress and warnings are sent to the given reporter and the given
+    /// source name is recorded in place of the one the branch was previously installed from.
+    ///
     /// # Errors
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When any [`AppStorage`] operation fails.
     /// * When any filesystem operation fails.
     /// * When any part of the installation process fails.
+    /// * When the release archive doesn't match its checksum ([`NaelError::ChecksumMismatch`]) or fails validation
+    ///   ([`NaelError::InvalidArchive`]).
+    /// * When the installation is pinned to a specific version ([`NaelError::BranchPinned`]).
     ///
     /// # Notes
     /// This function consumes the calling instance for safety.
-    /// If this operation returns an [`Err`] then it is possible that the original installation files may no longer be present on disk.
-    /// This would cause undesired behaviour with other operations.
+    /// The new release is only swapped into place once it has been fully extracted, so if this operation returns an [`Err`]
+    /// the original installation files should still be present on disk.
     ///
     /// In cases where it is desired to reobtain the installation call [`DalamudInstallation::get`]; This will validate the install
     /// is still valid before passing back an instance that can be used again.
@@ -154,8 +702,227 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// # Recommendations
     /// * Compare [`DalamudInstallation::get_version_info`] with [`DalamudInstallation::get_remote_version_info`] when available
     ///   to check if the installation actually needs to be updated before trying to update.
-    pub async fn update<RS: ReleaseSource>(self, release_source: &RS) -> Result<Self> {
-        Self::download_branch_impl(&self.branch_name, &self.storage, release_source).await
+    pub async fn update<RS: ReleaseSource>(
+        self,
+        release_source: &RS,
+        source_name: &str,
+        reporter: &Arc<dyn ProgressReporter>,
+    ) -> Result<Self, NaelError> {
+        if let Some(version) = self.get_pinned_version() {
+            return Err(NaelError::BranchPinned {
+                branch: self.get_remote_branch_name().to_owned(),
+                version: version.to_owned(),
+            });
+        }
+        Ok(Self::download_branch_impl(
+            &self.branch_name,
+            &self.storage,
+            release_source,
+            source_name,
+            reporter,
+        )
+        .await?)
+    }
+
+    /// Get the name of the remote branch this installation tracks, without any pinned version.
+    pub fn get_remote_branch_name(&self) -> &str {
+        split_installation_name(&self.branch_name).0
+    }
+
+    /// Get the version this installation is pinned to, or [`None`] if it tracks the latest version of its branch.
+    pub fn get_pinned_version(&self) -> Option<&str> {
+        split_installation_name(&self.branch_name).1
+    }
+
+    /// Roll the branch installation back to the most recent previous build kept in its history, consuming the instance and
+    /// returning a new one when [`Ok`].
+    ///
+    /// The current build of the branch is discarded and replaced with the previous build and its version information.
+    /// The active branch symlink does not need updating as the branch directory stays in the same location.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When any [`AppStorage`] operation fails.
+    /// * When any filesystem operation fails.
+    /// * When the branch has no previous builds in its history ([`NaelError::NoPreviousBuild`]).
+    ///
+    /// # Notes
+    /// If swapping the previous build into place fails, the current build is moved back before returning an [`Err`].
+    pub fn rollback(self) -> Result<Self, NaelError> {
+        let Some(previous_build) = Self::get_history_entries(&self.branch_name, &self.storage)?
+            .into_iter()
+            .next()
+        else {
+            return Err(NaelError::NoPreviousBuild(self.branch_name));
+        };
+
+        let branch_directory = self.storage.get_branch_directory(&self.branch_name)?;
+        let displaced_directory =
+            branch_directory.with_file_name(format!(".{}.rollback", self.branch_name));
+        if displaced_directory
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {displaced_directory:?}"))?
+        {
+            fs::remove_dir_all(&displaced_directory).with_context(|| {
+                format!("failed to remove leftover rollback directory {displaced_directory:?}")
+            })?;
+        }
+
+        let has_current_build = branch_directory
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {branch_directory:?}"))?;
+        if has_current_build {
+            fs::rename(&branch_directory, &displaced_directory).with_context(|| {
+                format!("failed to move {branch_directory:?} to {displaced_directory:?}")
+            })?;
+        }
+
+        if let Err(err) = fs::rename(&previous_build, &branch_directory) {
+            if has_current_build {
+                fs::rename(&displaced_directory, &branch_directory).with_context(|| {
+                    format!("failed to restore {branch_directory:?} after a failed rollback")
+                })?;
+            }
+            return Err(err)
+                .with_context(|| {
+                    format!("failed to move {previous_build:?} to {branch_directory:?}")
+                })
+                .map_err(Into::into);
+        }
+
+        if has_current_build {
+            fs::remove_dir_all(&displaced_directory).with_context(|| {
+                format!("failed to remove rolled back build {displaced_directory:?}")
+            })?;
+        }
+
+        Ok(self)
+    }
+
+    /// Verify the files of the branch installation against the manifest recorded when it was extracted.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When any [`AppStorage`] operation fails.
+    /// * When the branch installation has no manifest, e.g. it was installed before manifests were recorded
+    ///   ([`NaelError::MissingManifest`]).
+    /// * When unable to read any file inside of the branch directory.
+    pub fn verify(&self) -> Result<ManifestDiff, NaelError> {
+        let manifest_path = self.storage.get_branch_manifest_path(&self.branch_name)?;
+        if !manifest_path
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {manifest_path:?}"))?
+        {
+            return Err(NaelError::MissingManifest(self.branch_name.clone()));
+        }
+
+        Ok(Manifest::from_path_ref(&manifest_path)?.compare_directory(
+            &self.storage.get_branch_directory(&self.branch_name)?,
+            &[
+                self.storage.get_version_info_filename(),
+                self.storage.get_checksum_filename(),
+                self.storage.get_manifest_filename(),
+                self.storage.get_source_filename(),
+            ],
+        )?)
+    }
+
+    /// Repair the branch installation by extracting a fresh copy of the release it was installed from, consuming the instance
+    /// and returning a new one when [`Ok`].
+    ///
+    /// Unlike [`DalamudInstallation::update`] the installed version is never changed and the damaged build is discarded
+    /// instead of being kept in the branch history. The release archive is taken from the archive cache when it is still
+    /// cached, otherwise it is downloaded from the given release source again. Progress and warnings are sent to the given
+    /// reporter.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When any [`AppStorage`] operation fails.
+    /// * When the branch installation has no local version information ([`NaelError::MissingVersionInfo`]).
+    /// * When the release archive isn't cached and no release source is given ([`NaelError::MissingReleaseSource`]).
+    /// * When the release archive isn't cached and the installed version can no longer be found in the release source
+    ///   ([`NaelError::ReleaseNotFound`]).
+    /// * When any part of the installation process fails.
+    /// * When the release archive doesn't match its checksum ([`NaelError::ChecksumMismatch`]) or fails validation
+    ///   ([`NaelError::InvalidArchive`]).
+    pub async fn repair<RS: ReleaseSource>(
+        self,
+        release_source: Option<&RS>,
+        reporter: &Arc<dyn ProgressReporter>,
+    ) -> Result<Self, NaelError> {
+        if let Some(archive_path) = self.find_installed_archive(reporter.as_ref()) {
+            return Ok(Self::install_archive(
+                &self.branch_name,
+                &self.storage,
+                ReleaseArchive {
+                    path: archive_path,
+                    downloaded: false,
+                    expected_checksum: self.get_checksum()?,
+                    version_info: self.get_version_info_json()?,
+                    source_name: self.get_source_name()?,
+                },
+                false,
+                reporter,
+            )
+            .await?);
+        }
+
+        let Some(release_source) = release_source else {
+            return Err(NaelError::MissingReleaseSource(self.branch_name));
+        };
+        let release = if self.get_pinned_version().is_some() {
+            Self::locate_release(&self.branch_name, release_source).await?
+        } else {
+            let Some(version_info) = self.get_version_info()? else {
+                return Err(NaelError::MissingVersionInfo(self.branch_name));
+            };
+            if self.get_remote_version_info(release_source).await? == Some(version_info.clone()) {
+                Self::locate_release(&self.branch_name, release_source).await?
+            } else {
+                release_source
+                    .get_versioned_release(&self.branch_name, &version_info.assembly_version)
+                    .await?
+                    .ok_or_else(|| NaelError::ReleaseNotFound {
+                        branch: self.branch_name.clone(),
+                        version: Some(version_info.assembly_version.clone()),
+                    })?
+            }
+        };
+        Ok(Self::install_release_impl(
+            &self.branch_name,
+            &self.storage,
+            &release,
+            self.get_source_name()?.as_deref(),
+            false,
+            reporter,
+        )
+        .await?)
+    }
+
+    /// Find the release archive the branch installation was installed from inside of the archive cache, using the
+    /// checksum recorded when it was installed.
+    ///
+    /// Failures reading the cache are reported as a warning and treated as the archive not being cached.
+    fn find_installed_archive(&self, reporter: &dyn ProgressReporter) -> Option<PathBuf> {
+        let result = self
+            .get_checksum()
+            .map_err(anyhow::Error::from)
+            .and_then(|checksum| {
+                let Some(checksum) = checksum else {
+                    return Ok(None);
+                };
+                ArchiveCache::new(
+                    &self.storage.get_cache_directory()?,
+                    self.storage.get_cache_size_limit(),
+                )
+                .find_by_checksum(&checksum)
+            });
+        result.unwrap_or_else(|err| {
+            reporter.warn(&format!(
+                "Unable to read the release archive cache: {err:#}"
+            ));
+            None
+        })
     }
 
     /// Check a branch installation exists in the given storage.
@@ -164,7 +931,9 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When any [`AppStorage`] operation fails.
     /// * When unable to check if the given branch directory exists.
-    pub fn exists(branch_name: &str, storage: &Arc<S>) -> Result<bool> {
+    /// * When the branch name is not valid ([`NaelError::InvalidBranchName`]), see [`validate_installation_name`].
+    pub fn exists(branch_name: &str, storage: &Arc<S>) -> Result<bool, NaelError> {
+        validate_installation_name(branch_name)?;
         let branch_directory = storage.get_branch_directory(branch_name)?;
         if !branch_directory
             .try_exists()
@@ -182,7 +951,12 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When any [`AppStorage`] operation fails.
     /// * When unable to check if the given branch directory exists.
-    pub fn get(branch_name: &str, storage: &Arc<S>) -> Result<Option<DalamudInstallation<S>>> {
+    /// * When the branch name is not valid ([`NaelError::InvalidBranchName`]), see [`validate_installation_name`].
+    pub fn get(
+        branch_name: &str,
+        storage: &Arc<S>,
+    ) -> Result<Option<DalamudInstallation<S>>, NaelError> {
+        validate_installation_name(branch_name)?;
         let branch_directory = storage.get_branch_directory(branch_name)?;
         if !branch_directory
             .try_exists()
@@ -204,7 +978,7 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// * When any [`AppStorage`] operation fails.
     /// * When unable to check if the versions directory exists.
     /// * When unable to read relevant directories.
-    pub fn get_all(storage: &Arc<S>) -> Result<Option<Vec<DalamudInstallation<S>>>> {
+    pub fn get_all(storage: &Arc<S>) -> Result<Option<Vec<DalamudInstallation<S>>>, NaelError> {
         let branch_directory = storage.get_branches_directory()?;
         if !branch_directory
             .try_exists()
@@ -219,6 +993,11 @@ impl<S: AppStorage> DalamudInstallation<S> {
                 let path = entry.ok()?.path();
                 let file_name = path.file_name()?.to_str()?.to_owned();
 
+                // Hidden directories are used as working space during updates & rollbacks.
+                if file_name.starts_with('.') {
+                    return None;
+                }
+
                 Some(DalamudInstallation {
                     storage: Arc::clone(storage),
                     branch_name: file_name,
@@ -241,7 +1020,7 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// * When unable to read the active version symlink for any reason other than [`std::io::ErrorKind::NotFound`].
     /// * When canonicalizing the symlink fails for any reason other than [`std::io::ErrorKind::NotFound`].
     /// * When converting the directory name from [`std::ffi::OsStr`] to [`String`].
-    pub fn get_active(storage: &Arc<S>) -> Result<Option<DalamudInstallation<S>>> {
+    pub fn get_active(storage: &Arc<S>) -> Result<Option<DalamudInstallation<S>>, NaelError> {
         let active_branch_symlink = storage.get_active_branch_symlink()?;
 
         if let Err(err) = fs::read_link(&active_branch_symlink) {
@@ -280,18 +1059,15 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When any [`AppStorage`] operation fails.
     /// * When unable to check if the branch directory exists.
-    /// * When the branch directory does not exist.
+    /// * When the branch directory does not exist ([`NaelError::BranchNotInstalled`]).
     /// * When removing the existing active version symlink fails for any reason other than [`std::io::ErrorKind::NotFound`].
-    pub fn set_active(&self) -> Result<()> {
+    pub fn set_active(&self) -> Result<(), NaelError> {
         let branch_directory = self.storage.get_branch_directory(&self.branch_name)?;
         if !branch_directory
             .try_exists()
             .with_context(|| format!("unable to check existence of {branch_directory:?}"))?
         {
-            return Err(anyhow!(
-                "unable to find branch {} in versions directory",
-                self.branch_name
-            ));
+            return Err(NaelError::BranchNotInstalled(self.branch_name.clone()));
         }
 
         // Remove the active version and make a new symlink.
@@ -326,7 +1102,7 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// * When any [`AppStorage`] operation fails.
     /// * When unable to read the active version symlink for any reason other than [`std::io::ErrorKind::NotFound`].
     /// * When removing the active version symlink fails for any reason other than [`std::io::ErrorKind::NotFound`].
-    pub fn unset_active(storage: &S) -> Result<()> {
+    pub fn unset_active(storage: &S) -> Result<(), NaelError> {
         let active_branch_symlink = storage.get_active_branch_symlink()?;
 
         if let Err(err) = fs::read_link(&active_branch_symlink) {
@@ -353,7 +1129,7 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// # Errors
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When the call to [`DalamudInstallation::get_active`] fails.
-    pub fn is_active(&self) -> Result<bool> {
+    pub fn is_active(&self) -> Result<bool, NaelError> {
         let Some(active) = Self::get_active(&self.storage)
             .context("failed to check for the currently active branch")?
         else {
@@ -370,7 +1146,7 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When any [`AppStorage`] operation fails.
     /// * When unable to check if the branch directory exists.
-    pub fn get_location(&self) -> Result<Option<PathBuf>> {
+    pub fn get_location(&self) -> Result<Option<PathBuf>, NaelError> {
         let branch_directory = self.storage.get_branch_directory(&self.branch_name)?;
 
         if !branch_directory
@@ -390,16 +1166,25 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When any [`AppStorage`] operation fails.
     /// * When the returned version info is not valid JSON.
-    pub fn get_version_info(&self) -> Result<Option<DalamudVersionInfo>> {
-        Ok(Some(DalamudVersionInfo::from_path_ref(
-            &self
-                .storage
-                .get_branch_version_info_path(&self.branch_name)?,
-        )?))
+    pub fn get_version_info(&self) -> Result<Option<DalamudVersionInfo>, NaelError> {
+        let version_info_path = self
+            .storage
+            .get_branch_version_info_path(&self.branch_name)?;
+
+        if !version_info_path
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {version_info_path:?}"))?
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(DalamudVersionInfo::from_path_ref(&version_info_path)?))
     }
 
     /// Get the remote version information for the branch installation using the given release source.
     ///
+    /// For pinned installations this is the version information of the pinned version, or [`None`] if it can no longer be found.
+    ///
     /// # Errors
     /// * When a network failure occurs fetching the remote version information.
     /// * When the returned version info is not valid JSON.
@@ -407,11 +1192,18 @@ impl<S: AppStorage> DalamudInstallation<S> {
     pub async fn get_remote_version_info<RS: ReleaseSource>(
         &self,
         release_source: &RS,
-    ) -> Result<Option<DalamudVersionInfo>> {
+    ) -> Result<Option<DalamudVersionInfo>, NaelError> {
+        let Some(version_info_file) = self.get_remote_version_info_file(release_source).await?
+        else {
+            return Ok(None);
+        };
         Ok(Some(
-            release_source
-                .get_version_info_file(&self.branch_name)
-                .read_to_string()
+            version_info_file
+                .read_to_string_cached(
+                    &self
+                        .storage
+                        .get_branch_remote_version_info_cache_path(&self.branch_name)?,
+                )
                 .await?
                 .parse::<DalamudVersionInfo>()?,
         ))
@@ -427,7 +1219,7 @@ impl<S: AppStorage> DalamudInstallation<S> {
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When any [`AppStorage`] operation fails.
     /// * When the returned version info string is not valid JSON.
-    pub fn get_version_info_json(&self) -> Result<Option<String>> {
+    pub fn get_version_info_json(&self) -> Result<Option<String>, NaelError> {
         let version_info_path = self
             .storage
             .get_branch_version_info_path(&self.branch_name)?;
@@ -449,11 +1241,59 @@ impl<S: AppStorage> DalamudInstallation<S> {
         }
 
         // Validate that the file is actually valid JSON.
-        let _: IgnoredAny = serde_json::from_str(&content)?;
+        let _: IgnoredAny = serde_json::from_str(&content)
+            .with_context(|| format!("invalid version information at {version_info_path:?}"))?;
 
         Ok(Some(content))
     }
 
+    /// Get the SHA-256 checksum of the release archive the branch installation was extracted from.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When any [`AppStorage`] operation fails.
+    /// * When the checksum file cannot be read or does not contain a valid checksum.
+    pub fn get_checksum(&self) -> Result<Option<String>, NaelError> {
+        let checksum_path = self.storage.get_branch_checksum_path(&self.branch_name)?;
+        if !checksum_path
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {checksum_path:?}"))?
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(checksum::parse_sha256(
+            &fs::read_to_string(&checksum_path)
+                .with_context(|| format!("could not read file at {checksum_path:?}"))?,
+        )?))
+    }
+
+    /// Get the name of the release source the branch installation was installed from, or [`None`] if it was installed
+    /// before release source names were recorded.
+    ///
+    /// The name is the one given to [`DalamudInstallation::create`] or [`DalamudInstallation::update`], and is kept
+    /// when repairing.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When any [`AppStorage`] operation fails.
+    /// * When the release source name file cannot be read.
+    pub fn get_source_name(&self) -> Result<Option<String>, NaelError> {
+        let source_path = self.storage.get_branch_source_path(&self.branch_name)?;
+        if !source_path
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {source_path:?}"))?
+        {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&source_path)
+            .with_context(|| format!("could not read file at {source_path:?}"))?
+            .trim()
+            .to_owned();
+        Ok((!content.is_empty()).then_some(content))
+    }
+
     /// Get the remote version information for the branch installation by using the given release source.
     /// and returning it without serialization into [`DalamudVersionInfo`]
     ///
@@ -468,15 +1308,82 @@ impl<S: AppStorage> DalamudInstallation<S> {
     pub async fn get_remote_version_info_json<RS: ReleaseSource>(
         &self,
         release_source: &RS,
-    ) -> Result<Option<String>> {
-        let version_info_raw: String = release_source
-            .get_version_info_file(&self.branch_name)
-            .read_to_string()
+    ) -> Result<Option<String>, NaelError> {
+        let Some(version_info_file) = self.get_remote_version_info_file(release_source).await?
+        else {
+            return Ok(None);
+        };
+        let version_info_raw: String = version_info_file
+            .read_to_string_cached(
+                &self
+                    .storage
+                    .get_branch_remote_version_info_cache_path(&self.branch_name)?,
+            )
             .await?;
 
         // Validate that the returned response is actually valid JSON.
-        let _: IgnoredAny = serde_json::from_str(&version_info_raw)?;
+        let _: IgnoredAny = serde_json::from_str(&version_info_raw).with_context(|| {
+            format!(
+                "invalid remote version information for branch {}",
+                self.branch_name
+            )
+        })?;
 
         Ok(Some(version_info_raw))
     }
+
+    /// Get the remote version info file for the branch installation, taking a pinned version into account.
+    async fn get_remote_version_info_file<RS: ReleaseSource>(
+        &self,
+        release_source: &RS,
+    ) -> Result<Option<RemoteResource>> {
+        match self.get_pinned_version() {
+            Some(version) => Ok(release_source
+                .get_versioned_release(self.get_remote_branch_name(), version)
+                .await?
+                .and_then(|release| release.version_info_file)),
+            None => Ok(Some(
+                release_source
+                    .get_version_info_file(self.get_remote_branch_name())
+                    .await?,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_branch_and_pinned_names() {
+        for name in ["stg", "release", "stg@9.0.0.17", "v9@a1b2c3d"] {
+            validate_installation_name(name).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_path_names() {
+        for name in [
+            "",
+            "..",
+            "../../x",
+            "a/b",
+            "a\\b",
+            ".hidden",
+            "stg@",
+            "stg@../../x",
+            "stg@a/b",
+            "stg@.hidden",
+            "@9.0.0.17",
+        ] {
+            assert!(
+                matches!(
+                    validate_installation_name(name),
+                    Err(NaelError::InvalidBranchName(_))
+                ),
+                "{name:?} was accepted"
+            );
+        }
+    }
 }
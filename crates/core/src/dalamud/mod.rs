@@ -1,7 +1,9 @@
 mod management;
+mod project;
 mod sources;
 mod version_info;
 
 pub use management::*;
+pub use project::*;
 pub use sources::*;
 pub use version_info::*;
@@ -0,0 +1,116 @@
+use super::DalamudInstallation;
+use crate::{error::NaelError, fs::storage::AppStorage};
+use anyhow::{Context, Result, anyhow};
+use std::{
+    fmt::Debug,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The filenames of project files, in the order they are looked for inside of each directory.
+pub const PROJECT_FILENAMES: [&str; 2] = [".naelrc", "dalamud-version"];
+
+/// The character that starts a comment line inside of a project file.
+const PROJECT_FILE_COMMENT: char = '#';
+
+/// A file that declares which Dalamud branch a project targets, such as a `.naelrc` at the root of a plugin repository.
+///
+/// The first line that isn't empty or a `#` comment is the branch name, which may be pinned to a specific version with
+/// `<branch>@<version>` in the same way as when installing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectFile {
+    /// The path to the project file.
+    pub path: PathBuf,
+
+    /// The name of the branch installation the project targets.
+    pub branch_name: String,
+}
+
+impl ProjectFile {
+    /// Find the nearest project file by checking the given directory and then each of its parent directories.
+    ///
+    /// Returns [`None`] when no directory up to the root contains a project file.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to check if a project file exists.
+    /// * When the nearest project file cannot be read or does not name a branch.
+    pub fn find<P: AsRef<Path>>(directory: &P) -> Result<Option<Self>> {
+        for directory in directory.as_ref().ancestors() {
+            for filename in PROJECT_FILENAMES {
+                let path = directory.join(filename);
+                if path
+                    .try_exists()
+                    .with_context(|| format!("unable to check existence of {path:?}"))?
+                    && path.is_file()
+                {
+                    return Ok(Some(Self::from_path_ref(&path)?));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Read the project file at the given path.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When a failure occurs reading the file at the given path.
+    /// * When the file does not name a branch.
+    pub fn from_path_ref<P: AsRef<Path> + Debug>(path: &P) -> Result<Self> {
+        let branch_name = read_to_string(path)
+            .with_context(|| format!("failed to read file at {path:?}"))?
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with(PROJECT_FILE_COMMENT))
+            .ok_or_else(|| anyhow!("project file at {path:?} does not name a branch"))?
+            .to_owned();
+
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            branch_name,
+        })
+    }
+
+    /// Get the installation of the branch the project targets from the given storage.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When the call to [`DalamudInstallation::get`] fails.
+    pub fn get_installation<S: AppStorage>(
+        &self,
+        storage: &Arc<S>,
+    ) -> Result<Option<DalamudInstallation<S>>, NaelError> {
+        DalamudInstallation::get(&self.branch_name, storage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::storage::CompliantDiskStorage;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn rejects_traversal_branch_names() {
+        let directory = TempDir::new().unwrap();
+        fs::write(
+            directory.path().join(PROJECT_FILENAMES[0]),
+            "# Not a branch\n../../../some/dir\n",
+        )
+        .unwrap();
+        let nested_directory = directory.path().join("src");
+        fs::create_dir(&nested_directory).unwrap();
+
+        let project_file = ProjectFile::find(&nested_directory).unwrap().unwrap();
+        assert_eq!(project_file.branch_name, "../../../some/dir");
+
+        let storage = Arc::new(CompliantDiskStorage::new("dev", "Blooym", "NaelTest"));
+        assert!(matches!(
+            project_file.get_installation(&storage),
+            Err(NaelError::InvalidBranchName(_))
+        ));
+    }
+}
@@ -0,0 +1,169 @@
+use super::{ReleaseSource, VersionedRelease};
+use crate::{dalamud::DalamudVersionInfo, net::RemoteResource};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// The name of the branch at the root of the repository.
+///
+/// This name corresponds to the version of Dalamud that is stored at the root of the repository (e.g. /version.zip).
+///
+/// **Note: This does not represent the git branch name**.
+const OFFICIAL_DOWNLOAD_BASE_URL: &str = "https://goatcorp.github.io/dalamud-distrib";
+const OFFICIAL_ROOT_BRANCH_NAME: &str = "release";
+const OFFICIAL_RELEASE_ARCHIVE_FILENAME: &str = "latest.zip";
+const OFFICIAL_VERSION_INFO_FILENAME: &str = "version";
+
+/// The GitHub API & raw content URLs for the dalamud-distrib repository, used for looking up historical releases.
+const OFFICIAL_REPOSITORY_API_URL: &str = "https://api.github.com/repos/goatcorp/dalamud-distrib";
+const OFFICIAL_REPOSITORY_RAW_URL: &str =
+    "https://raw.githubusercontent.com/goatcorp/dalamud-distrib";
+
+/// The maximum amount of historical commits that will be searched when looking up a versioned release.
+const OFFICIAL_VERSION_SEARCH_LIMIT: u8 = 100;
+
+/// A [`ReleaseSource`] implementation for the official dalamud-distrib repository at `goatcorp/dalamud-distrib`.
+#[derive(Debug, Clone, Copy)]
+pub struct GoatcorpReleaseSource;
+
+impl Default for GoatcorpReleaseSource {
+    fn default() -> Self {
+        GoatcorpReleaseSource
+    }
+}
+
+/// A commit as returned by the GitHub commits API, only containing the fields that are used.
+#[derive(Debug, Deserialize)]
+struct GitHubCommit {
+    sha: String,
+}
+
+/// A git tree as returned by the GitHub trees API, only containing the fields that are used.
+#[derive(Debug, Deserialize)]
+struct GitHubTree {
+    tree: Vec<GitHubTreeEntry>,
+}
+
+/// A single entry inside of a [`GitHubTree`], only containing the fields that are used.
+#[derive(Debug, Deserialize)]
+struct GitHubTreeEntry {
+    path: String,
+}
+
+impl GoatcorpReleaseSource {
+    /// Get the path of a file relative to the repository root for the given branch.
+    fn get_branch_file_path(branch: &str, filename: &str) -> String {
+        if branch == OFFICIAL_ROOT_BRANCH_NAME {
+            filename.to_owned()
+        } else {
+            format!("{branch}/{filename}")
+        }
+    }
+}
+
+impl ReleaseSource for GoatcorpReleaseSource {
+    /// Get the [`RemoteResource`] for the given Dalamud branch release archive.
+    async fn get_release_archive_file(&self, branch: &str) -> Result<RemoteResource> {
+        Ok(RemoteResource::from_url(format!(
+            "{OFFICIAL_DOWNLOAD_BASE_URL}/{}",
+            Self::get_branch_file_path(branch, OFFICIAL_RELEASE_ARCHIVE_FILENAME)
+        )))
+    }
+
+    /// Get the [`RemoteResource`] for the given Dalamud branch version info file.
+    async fn get_version_info_file(&self, branch: &str) -> Result<RemoteResource> {
+        Ok(RemoteResource::from_url(format!(
+            "{OFFICIAL_DOWNLOAD_BASE_URL}/{}",
+            Self::get_branch_file_path(branch, OFFICIAL_VERSION_INFO_FILENAME)
+        )))
+    }
+
+    /// The official repository does not publish checksums.
+    async fn get_checksum_file(&self, _branch: &str) -> Result<Option<RemoteResource>> {
+        Ok(None)
+    }
+
+    /// Find a versioned release by walking the git history of the branch version info file and returning the
+    /// release assets from the first commit that matches the given version.
+    async fn get_versioned_release(
+        &self,
+        branch: &str,
+        version: &str,
+    ) -> Result<Option<VersionedRelease>> {
+        let version_info_path = Self::get_branch_file_path(branch, OFFICIAL_VERSION_INFO_FILENAME);
+        let archive_path = Self::get_branch_file_path(branch, OFFICIAL_RELEASE_ARCHIVE_FILENAME);
+
+        let commits: Vec<GitHubCommit> = serde_json::from_str(
+            &RemoteResource::from_url(format!(
+                "{OFFICIAL_REPOSITORY_API_URL}/commits?path={version_info_path}&per_page={OFFICIAL_VERSION_SEARCH_LIMIT}"
+            ))
+            .read_to_string()
+            .await
+            .context("failed to fetch release history")?,
+        )
+        .context("unable to deserialize release history")?;
+
+        for commit in commits {
+            let version_info_file = RemoteResource::from_url(format!(
+                "{OFFICIAL_REPOSITORY_RAW_URL}/{}/{version_info_path}",
+                commit.sha
+            ));
+
+            let raw_version_info = version_info_file.read_to_string().await.with_context(|| {
+                format!(
+                    "failed to fetch version information of commit {}",
+                    commit.sha
+                )
+            })?;
+            // Old commits may have version info in a format that can't be read, these are skipped over.
+            let Ok(version_info) = raw_version_info.parse::<DalamudVersionInfo>() else {
+                continue;
+            };
+            if !version_info.matches_version(version) {
+                continue;
+            }
+
+            return Ok(Some(VersionedRelease {
+                archive_file: RemoteResource::from_url(format!(
+                    "{OFFICIAL_REPOSITORY_RAW_URL}/{}/{archive_path}",
+                    commit.sha
+                )),
+                version_info_file: Some(version_info_file),
+                checksum_file: None,
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// Find every branch by looking for release archives at the root of the repository and inside of its top-level
+    /// directories.
+    async fn get_branches(&self) -> Result<Vec<String>> {
+        let tree: GitHubTree = serde_json::from_str(
+            &RemoteResource::from_url(format!(
+                "{OFFICIAL_REPOSITORY_API_URL}/git/trees/HEAD?recursive=1"
+            ))
+            .read_to_string()
+            .await
+            .context("failed to fetch repository tree")?,
+        )
+        .context("unable to deserialize repository tree")?;
+
+        let mut branches = tree
+            .tree
+            .into_iter()
+            .filter_map(|entry| match entry.path.rsplit_once('/') {
+                Some((directory, OFFICIAL_RELEASE_ARCHIVE_FILENAME))
+                    if !directory.contains('/') =>
+                {
+                    Some(directory.to_owned())
+                }
+                None if entry.path == OFFICIAL_RELEASE_ARCHIVE_FILENAME => {
+                    Some(OFFICIAL_ROOT_BRANCH_NAME.to_owned())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        branches.sort();
+        Ok(branches)
+    }
+}
@@ -0,0 +1,280 @@
+use super::{ReleaseSource, VersionedRelease};
+use crate::{dalamud::DalamudVersionInfo, error::NaelError, net::RemoteResource};
+use anyhow::{Context, Result};
+use reqwest::Url;
+use serde::Deserialize;
+use std::{collections::BTreeMap, sync::OnceLock};
+
+/// The base URL of the official Kamori instance, which is how the official launchers perform Dalamud updates.
+const OFFICIAL_KAMORI_BASE_URL: &str = "https://kamori.goats.dev";
+
+/// The path to the release metadata document, which contains cached release information for every track.
+const KAMORI_RELEASE_META_PATH: &str = "Dalamud/Release/Meta";
+
+/// The path to the version information of a single track, the track is given with the `track` query parameter.
+const KAMORI_VERSION_INFO_PATH: &str = "Dalamud/Release/VersionInfo";
+
+/// A single track entry inside of the Kamori release metadata document, only containing the fields that are used.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct KamoriTrack {
+    #[serde(alias = "AssemblyVersion")]
+    assembly_version: String,
+    #[serde(alias = "DownloadUrl")]
+    download_url: String,
+}
+
+/// A [`ReleaseSource`] implementation for the release metadata served by Kamori at `kamori.goats.dev`.
+///
+/// Each branch name corresponds to a track inside of the release metadata document. The document is fetched once on
+/// first use and reused for the lifetime of the instance.
+#[derive(Debug, Clone)]
+pub struct KamoriReleaseSource {
+    base_url: String,
+    meta: OnceLock<BTreeMap<String, KamoriTrack>>,
+}
+
+impl Default for KamoriReleaseSource {
+    fn default() -> Self {
+        Self::new(OFFICIAL_KAMORI_BASE_URL)
+    }
+}
+
+impl KamoriReleaseSource {
+    /// Create a new source that fetches release metadata from the Kamori instance at the given base URL.
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            meta: OnceLock::new(),
+        }
+    }
+
+    /// Get the release metadata document, fetching it from the remote when it hasn't been fetched yet.
+    async fn get_meta(&self) -> Result<&BTreeMap<String, KamoriTrack>> {
+        if let Some(meta) = self.meta.get() {
+            return Ok(meta);
+        }
+
+        let meta_file =
+            RemoteResource::from_url(format!("{}/{KAMORI_RELEASE_META_PATH}", self.base_url));
+        let meta = serde_json::from_str(
+            &meta_file
+                .read_to_string()
+                .await
+                .context("failed to fetch release metadata")?,
+        )
+        .with_context(|| format!("unable to deserialize resource at {}", meta_file.url))?;
+
+        Ok(self.meta.get_or_init(|| meta))
+    }
+
+    /// Get the metadata entry for the given track.
+    async fn get_track(&self, track: &str) -> Result<&KamoriTrack> {
+        self.get_meta().await?.get(track).ok_or_else(|| {
+            NaelError::ReleaseNotFound {
+                branch: track.to_owned(),
+                version: None,
+            }
+            .into()
+        })
+    }
+}
+
+impl ReleaseSource for KamoriReleaseSource {
+    /// Get the [`RemoteResource`] for the download URL of the given track.
+    async fn get_release_archive_file(&self, branch: &str) -> Result<RemoteResource> {
+        Ok(RemoteResource::from_url(
+            self.get_track(branch).await?.download_url.clone(),
+        ))
+    }
+
+    /// Get the [`RemoteResource`] for the version information of the given track.
+    ///
+    /// The release metadata document isn't fetched, so that checking for updates only needs a single conditional
+    /// request. Unknown tracks are reported by Kamori responding with a not found status instead.
+    async fn get_version_info_file(&self, branch: &str) -> Result<RemoteResource> {
+        let url = Url::parse_with_params(
+            &format!("{}/{KAMORI_VERSION_INFO_PATH}", self.base_url),
+            [("track", branch)],
+        )
+        .with_context(|| format!("invalid Kamori base URL {}", self.base_url))?;
+        Ok(RemoteResource::from_url(url.to_string()))
+    }
+
+    /// Kamori does not publish checksums.
+    async fn get_checksum_file(&self, _branch: &str) -> Result<Option<RemoteResource>> {
+        Ok(None)
+    }
+
+    /// Kamori only serves the latest release of each track, so only the current version of a track can be found.
+    async fn get_versioned_release(
+        &self,
+        branch: &str,
+        version: &str,
+    ) -> Result<Option<VersionedRelease>> {
+        let track = self.get_track(branch).await?;
+        let version_info = DalamudVersionInfo {
+            assembly_version: track.assembly_version.clone(),
+            git_sha: None,
+            revision: None,
+        };
+        if !version_info.matches_version(version) {
+            return Ok(None);
+        }
+
+        Ok(Some(VersionedRelease {
+            archive_file: self.get_release_archive_file(branch).await?,
+            version_info_file: Some(self.get_version_info_file(branch).await?),
+            checksum_file: None,
+        }))
+    }
+
+    /// Get the names of all tracks known to the release metadata.
+    async fn get_branches(&self) -> Result<Vec<String>> {
+        Ok(self.get_meta().await?.keys().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    const META: &str = r#"{
+        "release": {"assemblyVersion": "12.0.0.1", "downloadUrl": "https://example.com/release.zip"},
+        "stg": {"AssemblyVersion": "12.0.0.2", "DownloadUrl": "https://example.com/stg.zip"}
+    }"#;
+    const STG_VERSION_INFO: &str =
+        r#"{"assemblyVersion": "12.0.0.2", "gitSha": "0123456789abcdef"}"#;
+
+    /// Serve the Kamori endpoints from a local listener, returning the base URL of the stand-in instance.
+    async fn serve_kamori() -> String {
+        serve_kamori_counted().await.0
+    }
+
+    /// Serve the Kamori endpoints from a local listener, returning the base URL of the stand-in instance and the amount
+    /// of times the release metadata document has been requested from it.
+    async fn serve_kamori_counted() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let meta_requests = Arc::new(AtomicUsize::new(0));
+        let served_meta_requests = Arc::clone(&meta_requests);
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&buf[..read]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = match path {
+                    "/Dalamud/Release/Meta" => {
+                        served_meta_requests.fetch_add(1, Ordering::Relaxed);
+                        ("200 OK", META)
+                    }
+                    "/Dalamud/Release/VersionInfo?track=stg" => ("200 OK", STG_VERSION_INFO),
+                    _ => ("404 Not Found", ""),
+                };
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (format!("http://{address}/"), meta_requests)
+    }
+
+    #[tokio::test]
+    async fn finds_track_assets() {
+        let source = KamoriReleaseSource::new(&serve_kamori().await);
+        assert_eq!(
+            source.get_release_archive_file("stg").await.unwrap().url,
+            "https://example.com/stg.zip"
+        );
+
+        let version_info_file = source.get_version_info_file("stg").await.unwrap();
+        assert!(
+            version_info_file
+                .url
+                .ends_with("/Dalamud/Release/VersionInfo?track=stg")
+        );
+        assert_eq!(
+            DalamudVersionInfo::from_remote_file(&version_info_file)
+                .await
+                .unwrap()
+                .assembly_version,
+            "12.0.0.2"
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_track_is_not_found() {
+        let source = KamoriReleaseSource::new(&serve_kamori().await);
+        let err = NaelError::from(
+            source
+                .get_release_archive_file("missing")
+                .await
+                .unwrap_err(),
+        );
+        assert!(matches!(
+            err,
+            NaelError::ReleaseNotFound { branch, version: None } if branch == "missing"
+        ));
+    }
+
+    #[tokio::test]
+    async fn version_info_does_not_fetch_meta() {
+        let (base_url, meta_requests) = serve_kamori_counted().await;
+        let source = KamoriReleaseSource::new(&base_url);
+        let version_info_file = source.get_version_info_file("stg").await.unwrap();
+        DalamudVersionInfo::from_remote_file(&version_info_file)
+            .await
+            .unwrap();
+        assert_eq!(meta_requests.load(Ordering::Relaxed), 0);
+
+        let missing_file = source.get_version_info_file("missing").await.unwrap();
+        let err = NaelError::from(missing_file.read_to_string().await.unwrap_err());
+        assert!(matches!(err, NaelError::HttpStatus { status: 404, .. }));
+    }
+
+    #[tokio::test]
+    async fn lists_tracks_as_branches() {
+        let source = KamoriReleaseSource::new(&serve_kamori().await);
+        assert_eq!(source.get_branches().await.unwrap(), ["release", "stg"]);
+    }
+
+    #[tokio::test]
+    async fn only_finds_current_version() {
+        let source = KamoriReleaseSource::new(&serve_kamori().await);
+        let release = source
+            .get_versioned_release("stg", "12.0.0.2")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(release.archive_file.url, "https://example.com/stg.zip");
+        assert!(release.checksum_file.is_none());
+
+        assert!(
+            source
+                .get_versioned_release("stg", "12.0.0.1")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use super::{ReleaseSource, VersionedRelease};
+use crate::{dalamud::DalamudVersionInfo, net::RemoteResource};
+use anyhow::{Result, anyhow};
+use std::{fmt::Debug, path::Path};
+
+/// A [`ReleaseSource`] implementation that serves a single release archive from the local filesystem for every branch,
+/// allowing installations without network access.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalFileReleaseSource {
+    archive_file: RemoteResource,
+    version_info_file: Option<RemoteResource>,
+}
+
+impl LocalFileReleaseSource {
+    /// Create a new source for the release archive at the given path.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When the path cannot be resolved.
+    pub fn new<P: AsRef<Path> + Debug>(archive_path: &P) -> Result<Self> {
+        Ok(Self {
+            archive_file: RemoteResource::from_path(archive_path)?,
+            version_info_file: None,
+        })
+    }
+
+    /// Set the path of the version info file that belongs to the release archive.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When the path cannot be resolved.
+    pub fn with_version_info_path<P: AsRef<Path> + Debug>(
+        mut self,
+        version_info_path: &P,
+    ) -> Result<Self> {
+        self.version_info_file = Some(RemoteResource::from_path(version_info_path)?);
+        Ok(self)
+    }
+}
+
+impl ReleaseSource for LocalFileReleaseSource {
+    async fn get_release_archive_file(&self, _branch: &str) -> Result<RemoteResource> {
+        Ok(self.archive_file.clone())
+    }
+
+    async fn get_version_info_file(&self, _branch: &str) -> Result<RemoteResource> {
+        self.version_info_file.clone().ok_or_else(|| {
+            anyhow!("no version info file was provided for the local release archive")
+        })
+    }
+
+    async fn get_checksum_file(&self, _branch: &str) -> Result<Option<RemoteResource>> {
+        Ok(None)
+    }
+
+    /// The release archive can only be matched to a version when a version info file was provided.
+    async fn get_versioned_release(
+        &self,
+        _branch: &str,
+        version: &str,
+    ) -> Result<Option<VersionedRelease>> {
+        let Some(version_info_file) = &self.version_info_file else {
+            return Ok(None);
+        };
+        if !DalamudVersionInfo::from_remote_file(version_info_file)
+            .await?
+            .matches_version(version)
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(VersionedRelease {
+            archive_file: self.archive_file.clone(),
+            version_info_file: Some(version_info_file.clone()),
+            checksum_file: None,
+        }))
+    }
+
+    /// The same release archive is served for every branch, so there are no specific branches to list.
+    async fn get_branches(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
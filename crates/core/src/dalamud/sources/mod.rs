@@ -0,0 +1,63 @@
+mod goatcorp;
+mod kamori;
+mod local;
+mod template;
+
+pub use goatcorp::*;
+pub use kamori::*;
+pub use local::*;
+pub use template::*;
+
+use crate::net::RemoteResource;
+use anyhow::Result;
+use std::future::Future;
+
+/// An implementation that enables locating Dalamud release assets.
+pub trait ReleaseSource {
+    /// Get the [`RemoteResource`] for the latest release archive of the given branch.
+    fn get_release_archive_file(
+        &self,
+        branch: &str,
+    ) -> impl Future<Output = Result<RemoteResource>> + Send;
+
+    /// Get the [`RemoteResource`] for the latest version info file of the given branch.
+    fn get_version_info_file(
+        &self,
+        branch: &str,
+    ) -> impl Future<Output = Result<RemoteResource>> + Send;
+
+    /// Get the [`RemoteResource`] for the published SHA-256 checksum of the latest release archive of the given branch.
+    ///
+    /// Returns [`None`] when the source does not publish checksums.
+    fn get_checksum_file(
+        &self,
+        branch: &str,
+    ) -> impl Future<Output = Result<Option<RemoteResource>>> + Send;
+
+    /// Find the release assets for a specific version of the given branch.
+    ///
+    /// The version is matched against release version information using [`crate::dalamud::DalamudVersionInfo::matches_version`].
+    /// When no release of the branch matches the version [`None`] will be returned.
+    fn get_versioned_release(
+        &self,
+        branch: &str,
+        version: &str,
+    ) -> impl Future<Output = Result<Option<VersionedRelease>>> + Send;
+
+    /// Get the names of every branch the source has releases for, sorted by name.
+    ///
+    /// Returns an empty list when the source cannot tell which branches it has, such as when it serves the same
+    /// release for every branch.
+    fn get_branches(&self) -> impl Future<Output = Result<Vec<String>>> + Send;
+}
+
+/// The remote assets that make up a specific release of a branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionedRelease {
+    /// The release archive for this version.
+    pub archive_file: RemoteResource,
+    /// The version info file for this version, if the source has one.
+    pub version_info_file: Option<RemoteResource>,
+    /// The published SHA-256 checksum of the release archive for this version, if the source publishes one.
+    pub checksum_file: Option<RemoteResource>,
+}
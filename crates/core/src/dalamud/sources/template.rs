@@ -0,0 +1,172 @@
+use super::{ReleaseSource, VersionedRelease};
+use crate::{dalamud::DalamudVersionInfo, error::NaelError, net::RemoteResource};
+use anyhow::Result;
+use std::io;
+
+const DEFAULT_ROOT_BRANCH_NAME: &str = "release";
+const DEFAULT_RELEASE_ARCHIVE_FILENAME: &str = "latest.zip";
+const DEFAULT_VERSION_INFO_FILENAME: &str = "version";
+
+/// A [`ReleaseSource`] implementation for any distribution that follows the same layout as `goatcorp/dalamud-distrib`,
+/// such as self-hosted mirrors or the distribution of a fork.
+///
+/// Release assets are located at `{base_url}/{branch}/{filename}`, apart from the root branch which is located at
+/// `{base_url}/{filename}`. When a versions directory is set, specific versions of a branch are located at
+/// `{base_url}/{branch}/{versions_directory}/{version}/{filename}`. When a checksum filename is set, the published SHA-256
+/// checksum of each release archive is located next to it. Branches can only be listed when their names have been set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlTemplateReleaseSource {
+    base_url: String,
+    root_branch_name: String,
+    release_archive_filename: String,
+    version_info_filename: String,
+    versions_directory: Option<String>,
+    checksum_filename: Option<String>,
+    branch_names: Vec<String>,
+}
+
+impl UrlTemplateReleaseSource {
+    /// Create a new source for the distribution at the given base URL using the same names as `goatcorp/dalamud-distrib`.
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            root_branch_name: DEFAULT_ROOT_BRANCH_NAME.to_owned(),
+            release_archive_filename: DEFAULT_RELEASE_ARCHIVE_FILENAME.to_owned(),
+            version_info_filename: DEFAULT_VERSION_INFO_FILENAME.to_owned(),
+            versions_directory: None,
+            checksum_filename: None,
+            branch_names: Vec::new(),
+        }
+    }
+
+    /// Set the name of the branch that is stored at the root of the distribution.
+    pub fn with_root_branch_name(mut self, root_branch_name: &str) -> Self {
+        self.root_branch_name = root_branch_name.to_owned();
+        self
+    }
+
+    /// Set the filename of the release archive inside of each branch.
+    pub fn with_release_archive_filename(mut self, release_archive_filename: &str) -> Self {
+        self.release_archive_filename = release_archive_filename.to_owned();
+        self
+    }
+
+    /// Set the filename of the version info file inside of each branch.
+    pub fn with_version_info_filename(mut self, version_info_filename: &str) -> Self {
+        self.version_info_filename = version_info_filename.to_owned();
+        self
+    }
+
+    /// Set the name of the directory inside of each branch that contains specific versions of the branch.
+    pub fn with_versions_directory(mut self, versions_directory: &str) -> Self {
+        self.versions_directory = Some(versions_directory.trim_matches('/').to_owned());
+        self
+    }
+
+    /// Set the filename of the file containing the SHA-256 checksum of the release archive inside of each branch.
+    pub fn with_checksum_filename(mut self, checksum_filename: &str) -> Self {
+        self.checksum_filename = Some(checksum_filename.to_owned());
+        self
+    }
+
+    /// Set the names of the branches the distribution has, as a distribution cannot be asked which branches it has.
+    pub fn with_branch_names(mut self, branch_names: &[String]) -> Self {
+        self.branch_names = branch_names.to_vec();
+        self.branch_names.sort();
+        self
+    }
+
+    /// Get the URL of the directory that contains the assets of the given branch.
+    fn get_branch_url(&self, branch: &str) -> String {
+        if branch == self.root_branch_name {
+            self.base_url.clone()
+        } else {
+            format!("{}/{branch}", self.base_url)
+        }
+    }
+}
+
+impl ReleaseSource for UrlTemplateReleaseSource {
+    async fn get_release_archive_file(&self, branch: &str) -> Result<RemoteResource> {
+        Ok(RemoteResource::from_url(format!(
+            "{}/{}",
+            self.get_branch_url(branch),
+            self.release_archive_filename
+        )))
+    }
+
+    async fn get_version_info_file(&self, branch: &str) -> Result<RemoteResource> {
+        Ok(RemoteResource::from_url(format!(
+            "{}/{}",
+            self.get_branch_url(branch),
+            self.version_info_filename
+        )))
+    }
+
+    async fn get_checksum_file(&self, branch: &str) -> Result<Option<RemoteResource>> {
+        Ok(self.checksum_filename.as_ref().map(|checksum_filename| {
+            RemoteResource::from_url(format!(
+                "{}/{checksum_filename}",
+                self.get_branch_url(branch)
+            ))
+        }))
+    }
+
+    /// Versions can only be found when a versions directory has been set, otherwise [`None`] is always returned. A version
+    /// is only treated as missing when its version info file doesn't exist, other failures to read it are returned.
+    async fn get_versioned_release(
+        &self,
+        branch: &str,
+        version: &str,
+    ) -> Result<Option<VersionedRelease>> {
+        let Some(versions_directory) = &self.versions_directory else {
+            return Ok(None);
+        };
+
+        let version_url = format!(
+            "{}/{versions_directory}/{version}",
+            self.get_branch_url(branch)
+        );
+        let version_info_file =
+            RemoteResource::from_url(format!("{version_url}/{}", self.version_info_filename));
+        let raw_version_info = match version_info_file.read_to_string().await {
+            Ok(raw_version_info) => raw_version_info,
+            Err(err) if is_not_found(&err) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let Ok(version_info) = raw_version_info.parse::<DalamudVersionInfo>() else {
+            return Ok(None);
+        };
+        if !version_info.matches_version(version) {
+            return Ok(None);
+        }
+
+        Ok(Some(VersionedRelease {
+            archive_file: RemoteResource::from_url(format!(
+                "{version_url}/{}",
+                self.release_archive_filename
+            )),
+            version_info_file: Some(version_info_file),
+            checksum_file: self.checksum_filename.as_ref().map(|checksum_filename| {
+                RemoteResource::from_url(format!("{version_url}/{checksum_filename}"))
+            }),
+        }))
+    }
+
+    /// Only the branch names that have been set are returned.
+    async fn get_branches(&self) -> Result<Vec<String>> {
+        Ok(self.branch_names.clone())
+    }
+}
+
+/// Check whether an error from reading a resource was caused by the resource not existing, either remotely or locally.
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<NaelError>(),
+            Some(NaelError::HttpStatus { status: 404, .. })
+        ) || cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|err| err.kind() == io::ErrorKind::NotFound)
+    })
+}
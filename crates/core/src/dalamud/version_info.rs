@@ -3,11 +3,6 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::{fmt::Debug, fs::read_to_string, path::Path, str::FromStr};
 
-// Note: https://kamori.goats.dev/Dalamud/Release/Meta exists and has caches of Dalamud releases and is how
-// the official launchers perform updates.
-// Right now this URL isn't being used, but it could be in the future to fetch releases in a better way, so
-// it is being left as a note here.
-
 /// Version information for a Dalamud release.
 ///
 /// # Warning
@@ -16,16 +11,19 @@ use std::{fmt::Debug, fs::read_to_string, path::Path, str::FromStr};
 ///
 /// # Compatibility
 /// This struct was built by manually looking at the `version` file on the official `goatcorp/dalamud-distrib` repository.
-/// it may not work with 3rd party release sources.
+/// it may not work with 3rd party release sources. The camelCase field names used by Kamori are also accepted.
 ///
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct DalamudVersionInfo {
     /// Assembly version string of Dalamud at release.
+    #[serde(alias = "assemblyVersion")]
     pub assembly_version: String,
     /// Git commit hash of the Dalamud at release.
+    #[serde(alias = "gitSha")]
     pub git_sha: Option<String>,
     /// Revision number of Dalamud at release.
+    #[serde(alias = "revision")]
     pub revision: Option<String>,
 }
 
@@ -36,7 +34,26 @@ impl FromStr for DalamudVersionInfo {
     }
 }
 
+/// The minimum length a git sha prefix must be to be considered when matching a version.
+const MIN_GIT_SHA_PREFIX_LEN: usize = 7;
+
 impl DalamudVersionInfo {
+    /// Check whether the given version string refers to this release.
+    ///
+    /// A version matches when it is equal to the assembly version, or when it is the git sha (or a prefix of at
+    /// least 7 characters of it) of the release.
+    pub fn matches_version(&self, version: &str) -> bool {
+        if self.assembly_version == version {
+            return true;
+        }
+        self.git_sha.as_ref().is_some_and(|git_sha| {
+            version.len() >= MIN_GIT_SHA_PREFIX_LEN
+                && git_sha
+                    .to_ascii_lowercase()
+                    .starts_with(&version.to_ascii_lowercase())
+        })
+    }
+
     /// Get the file at the given path and returns a [`DalamudVersionInfo`] from it.
     ///
     /// # Errors
@@ -0,0 +1,121 @@
+use std::io;
+
+/// The error returned when managing a Dalamud installation fails.
+///
+/// Each variant describes a specific kind of failure so callers can react to it without matching on the error message,
+/// anything that doesn't fit a more specific variant is returned as [`NaelError::Other`]. Variants may be added in
+/// future releases but existing variants will keep their meaning.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum NaelError {
+    /// The branch is not installed locally.
+    #[error("branch {0} is not installed")]
+    BranchNotInstalled(String),
+
+    /// The branch is already installed locally, obtain an instance and update it instead.
+    #[error("branch {0} is already installed")]
+    BranchAlreadyInstalled(String),
+
+    /// The branch name is not valid, such as a branch pinned to an empty version or a name that contains a path.
+    #[error("branch name {0} is not valid")]
+    InvalidBranchName(String),
+
+    /// The branch is pinned to a specific version and cannot be updated.
+    #[error("branch {branch} is pinned to version {version} and cannot be updated")]
+    BranchPinned { branch: String, version: String },
+
+    /// The release source has no release of the branch, or of the requested version of it.
+    #[error("unable to find {} in the release source", describe_release(.branch, .version.as_deref()))]
+    ReleaseNotFound {
+        branch: String,
+        version: Option<String>,
+    },
+
+    /// The branch has no previous builds in its history to roll back to.
+    #[error("branch {0} has no previous builds to roll back to")]
+    NoPreviousBuild(String),
+
+    /// The branch has no manifest to verify its files against.
+    #[error("branch {0} has no manifest to verify against, reinstall it to create one")]
+    MissingManifest(String),
+
+    /// The branch has no local version information.
+    #[error("branch {0} has no version information")]
+    MissingVersionInfo(String),
+
+    /// The release archive of the branch isn't cached and there is no release source to download it from again.
+    #[error("branch {0} has no cached release archive or release source to repair it from")]
+    MissingReleaseSource(String),
+
+    /// The checksum of a release archive doesn't match the published or previously recorded checksum.
+    #[error(
+        "release archive checksum mismatch (expected {expected}, got {actual}), refusing to extract"
+    )]
+    ChecksumMismatch { expected: String, actual: String },
+
+    /// A release archive is not supported, contains an unsafe entry or exceeds the extraction limits.
+    #[error("{0}")]
+    InvalidArchive(String),
+
+    /// A server responded to a network request with a non-success status.
+    #[error("network request to {url} failed with status {status}")]
+    HttpStatus { url: String, status: u16 },
+
+    /// A network request failed to complete, such as when the connection fails or times out.
+    #[error(transparent)]
+    Network(anyhow::Error),
+
+    /// A filesystem operation was denied, such as creating the active branch symlink without the required privileges.
+    #[error(transparent)]
+    PermissionDenied(anyhow::Error),
+
+    /// Any other failure.
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<anyhow::Error> for NaelError {
+    /// Convert an error into the most specific variant it matches, by either finding a [`NaelError`] it was created
+    /// from or by classifying the errors it was caused by.
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<NaelError>() {
+            Ok(err) => return err,
+            Err(err) => err,
+        };
+        if err.chain().any(|cause| cause.is::<reqwest::Error>()) {
+            Self::Network(err)
+        } else if err.chain().any(|cause| {
+            cause
+                .downcast_ref::<io::Error>()
+                .is_some_and(is_permission_denied)
+        }) {
+            Self::PermissionDenied(err)
+        } else {
+            Self::Other(err)
+        }
+    }
+}
+
+impl From<io::Error> for NaelError {
+    fn from(err: io::Error) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+/// Check whether an IO error was caused by missing permissions, including missing the privilege to create symlinks on
+/// Windows.
+fn is_permission_denied(err: &io::Error) -> bool {
+    /// The Windows error code for `ERROR_PRIVILEGE_NOT_HELD`.
+    const ERROR_PRIVILEGE_NOT_HELD: i32 = 1314;
+
+    err.kind() == io::ErrorKind::PermissionDenied
+        || (cfg!(windows) && err.raw_os_error() == Some(ERROR_PRIVILEGE_NOT_HELD))
+}
+
+/// Describe a release of a branch for an error message, e.g. `version 9.0.0.17 of branch stg`.
+fn describe_release(branch: &str, version: Option<&str>) -> String {
+    match version {
+        Some(version) => format!("version {version} of branch {branch}"),
+        None => format!("branch {branch}"),
+    }
+}
@@ -1,35 +1,768 @@
+use crate::{
+    error::NaelError,
+    progress::{ProgressKind, ProgressReporter, ProgressTask},
+};
 use anyhow::{Context, Result};
-use indicatif::{ProgressBar, ProgressStyle};
-use std::{fmt::Debug, fs::File, path::Path, time::Duration};
+use flate2::read::GzDecoder;
+use std::{
+    fmt::Debug,
+    fs::{self, File},
+    io::{self, Read, Seek},
+    path::{Path, PathBuf},
+};
+use tar::EntryType;
 use zip::ZipArchive;
 
-/// Extract an archive to a destination path, overwriting existing files.
+/// The magic bytes at the start of a zip archive.
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// The magic bytes at the start of an empty zip archive, which only contains the end of central directory record.
+const ZIP_EMPTY_MAGIC: &[u8] = b"PK\x05\x06";
+
+/// The magic bytes at the start of a gzip stream.
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+
+/// The magic bytes at the start of a zstd frame.
+const ZSTD_MAGIC: &[u8] = b"\x28\xb5\x2f\xfd";
+
+/// Limits on the contents of an archive that will be extracted, which protect against archives that decompress to far
+/// more data than expected (zip bombs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ExtractLimits {
+    /// The maximum total uncompressed size of every entry in bytes.
+    pub max_size: u64,
+    /// The maximum amount of entries.
+    pub max_entries: u64,
+}
+
+/// The formats of archive that can be extracted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArchiveFormat {
+    /// A zip archive.
+    Zip,
+    /// A tar archive compressed with gzip (`.tar.gz`).
+    TarGz,
+    /// A tar archive compressed with zstd (`.tar.zst`).
+    TarZst,
+}
+
+impl ArchiveFormat {
+    /// Detect the format of the archive at the given path from the magic bytes at the start of it.
+    ///
+    /// # Notes
+    /// The file extension isn't used as release archives are stored under a name derived from their URL while they are
+    /// downloaded and cached.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to read the file at the given path.
+    /// * When the file isn't in any of the supported formats.
+    pub(crate) fn detect<P: AsRef<Path> + Debug>(path: &P) -> Result<Self> {
+        let mut magic = Vec::with_capacity(4);
+        File::open(path)
+            .with_context(|| format!("failed to open file {path:?}"))?
+            .take(4)
+            .read_to_end(&mut magic)
+            .with_context(|| format!("failed to read file {path:?}"))?;
+
+        if magic.starts_with(ZIP_MAGIC) || magic.starts_with(ZIP_EMPTY_MAGIC) {
+            Ok(Self::Zip)
+        } else if magic.starts_with(GZIP_MAGIC) {
+            Ok(Self::TarGz)
+        } else if magic.starts_with(ZSTD_MAGIC) {
+            Ok(Self::TarZst)
+        } else {
+            Err(NaelError::InvalidArchive(format!(
+                "{path:?} is not a supported archive, only zip, tar.gz and tar.zst archives can be extracted"
+            ))
+            .into())
+        }
+    }
+}
+
+/// Extract a zip, tar.gz or tar.zst archive to a destination path, overwriting existing files.
+///
+/// Progress is sent to the given reporter under the given name, measured in uncompressed bytes with an update for
+/// every extracted entry. The total size and amount of entries are only known upfront for zip archives.
 ///
 /// # Errors
 /// This function will return an error in the following situations, but is not limited to just these cases:
-/// * When unable to open the archive at the archive path for reading.
+/// * When unable to open the archive at the archive path for reading or it isn't in a supported format.
+/// * When the archive exceeds the given limits or contains an entry that would be extracted outside of the destination path.
 /// * When unable to extract the archive to the destination path.
-pub fn extract_with_progress_bar<P: AsRef<Path> + Debug, D: AsRef<Path> + Debug>(
+pub fn extract_with_progress<P: AsRef<Path> + Debug, D: AsRef<Path> + Debug>(
     archive_path: &P,
     destination_path: &D,
+    name: &str,
+    limits: ExtractLimits,
+    reporter: &dyn ProgressReporter,
+) -> Result<()> {
+    let format = ArchiveFormat::detect(archive_path)?;
+    let file = File::open(archive_path)
+        .with_context(|| format!("failed to open file {archive_path:?}"))?;
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut archive = ZipArchive::new(file)
+                .with_context(|| format!("failed to read archive {archive_path:?}"))?;
+            let mut progress = ProgressTask::start(
+                reporter,
+                ProgressKind::Extract,
+                name,
+                Some(get_uncompressed_size(&mut archive)?),
+                Some(archive.len() as u64),
+            );
+            extract_zip(&mut archive, destination_path, limits, |path, size| {
+                progress.entry(path, size)
+            })?;
+            progress.finish();
+        }
+        ArchiveFormat::TarGz | ArchiveFormat::TarZst => {
+            let reader: Box<dyn Read> = match format {
+                ArchiveFormat::TarZst => Box::new(
+                    zstd::Decoder::new(file)
+                        .with_context(|| format!("failed to read archive {archive_path:?}"))?,
+                ),
+                _ => Box::new(GzDecoder::new(file)),
+            };
+            let mut progress =
+                ProgressTask::start(reporter, ProgressKind::Extract, name, None, None);
+            extract_tar(reader, destination_path, limits, |path, size| {
+                progress.entry(path, size)
+            })?;
+            progress.finish();
+        }
+    }
+
+    Ok(())
+}
+
+/// Extract every entry of a zip archive to a destination path one by one, overwriting existing files.
+///
+/// The path of each entry relative to the destination path and its uncompressed size are given to `on_entry` once it
+/// has been extracted.
+///
+/// # Notes
+/// The whole archive is validated against the limits before anything is extracted, and the amount of data actually
+/// decompressed is counted while extracting so that entries which declare a smaller size than they have are caught too.
+///
+/// # Errors
+/// This function will return an error in the following situations, but is not limited to just these cases:
+/// * When the archive has more entries or a larger total uncompressed size than the limits allow.
+/// * When an entry has an absolute path or a path that would be outside of the destination path.
+/// * When a symlink entry points to a path outside of the destination path.
+/// * When an entry would be written through a symlink extracted by an earlier entry.
+/// * When an entry of the archive cannot be read.
+/// * When any filesystem operation fails.
+pub(crate) fn extract_zip<R: Read + Seek, D: AsRef<Path> + Debug, F: FnMut(&Path, u64)>(
+    archive: &mut ZipArchive<R>,
+    destination_path: &D,
+    limits: ExtractLimits,
+    mut on_entry: F,
 ) -> Result<()> {
-    let extract_progress_bar = ProgressBar::new_spinner();
-    extract_progress_bar.set_style(
-        ProgressStyle::default_spinner()
-            .template("[{elapsed_precise}] {spinner:.green} {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
-    );
-    extract_progress_bar.set_message("Extracting archive...");
-    extract_progress_bar.enable_steady_tick(Duration::from_millis(100));
-
-    let mut archive = ZipArchive::new(
-        File::open(archive_path)
-            .with_context(|| format!("failed to open file {archive_path:?}"))?,
-    )?;
-    archive.extract(destination_path)?;
-
-    extract_progress_bar.finish_and_clear();
+    if archive.len() as u64 > limits.max_entries {
+        return Err(NaelError::InvalidArchive(format!(
+            "archive contains {} entries which is more than the limit of {}",
+            archive.len(),
+            limits.max_entries
+        ))
+        .into());
+    }
+    let declared_size = get_uncompressed_size(archive)?;
+    if declared_size > limits.max_size {
+        return Err(NaelError::InvalidArchive(format!(
+            "archive has an uncompressed size of {declared_size} bytes which is more than the limit of {} bytes",
+            limits.max_size
+        ))
+        .into());
+    }
+
+    let mut writer = EntryWriter::new(destination_path.as_ref(), limits)?;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .with_context(|| format!("failed to read entry {index} of archive"))?;
+        let relative_path = writer.begin_entry(entry.name())?;
+        let entry_size = entry.size();
+
+        if entry.is_dir() {
+            writer.write_directory(&relative_path)?;
+        } else if entry.is_symlink() {
+            let mut target = String::new();
+            writer
+                .limit_reader(&mut entry)
+                .read_to_string(&mut target)
+                .with_context(|| format!("failed to read symlink target of {relative_path:?}"))?;
+            writer.write_symlink(&relative_path, &target)?;
+        } else {
+            let mode = entry.unix_mode();
+            writer.write_file(&relative_path, &mut entry, mode)?;
+        }
+
+        on_entry(&relative_path, entry_size);
+    }
+
+    Ok(())
+}
+
+/// Extract every entry of a tar archive read from the given reader to a destination path one by one, overwriting
+/// existing files.
+///
+/// The path of each entry relative to the destination path and its size are given to `on_entry` once it has been
+/// extracted.
+///
+/// # Notes
+/// As tar archives have no index, the limits are enforced while extracting instead of beforehand.
+///
+/// # Errors
+/// This function will return an error in the following situations, but is not limited to just these cases:
+/// * When the archive has more entries or a larger total size than the limits allow.
+/// * When an entry has an absolute path or a path that would be outside of the destination path.
+/// * When a symlink entry points to a path outside of the destination path.
+/// * When an entry would be written through a symlink extracted by an earlier entry.
+/// * When the archive contains hard links, devices or other entries that aren't files, directories or symlinks.
+/// * When an entry of the archive cannot be read.
+/// * When any filesystem operation fails.
+pub(crate) fn extract_tar<R: Read, D: AsRef<Path> + Debug, F: FnMut(&Path, u64)>(
+    reader: R,
+    destination_path: &D,
+    limits: ExtractLimits,
+    mut on_entry: F,
+) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    let mut writer = EntryWriter::new(destination_path.as_ref(), limits)?;
+    for entry in archive.entries().context("failed to read archive")? {
+        let mut entry = entry.context("failed to read entry of archive")?;
+        let entry_type = entry.header().entry_type();
+        if entry_type == EntryType::XGlobalHeader {
+            continue;
+        }
+
+        let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+        let relative_path = writer.begin_entry(&name)?;
+        let entry_size = entry.size();
+        if entry_type == EntryType::Directory && relative_path.as_os_str().is_empty() {
+            // The destination itself (e.g. `./`), which already exists.
+            continue;
+        }
+
+        match entry_type {
+            EntryType::Directory => writer.write_directory(&relative_path)?,
+            EntryType::Symlink => {
+                let target = entry
+                    .link_name_bytes()
+                    .map(|target| String::from_utf8_lossy(&target).into_owned())
+                    .ok_or_else(|| {
+                        NaelError::InvalidArchive(format!(
+                            "symlink {relative_path:?} has no target"
+                        ))
+                    })?;
+                writer.write_symlink(&relative_path, &target)?;
+            }
+            EntryType::Regular | EntryType::Continuous => {
+                let mode = entry.header().mode().ok();
+                writer.write_file(&relative_path, &mut entry, mode)?;
+            }
+            entry_type => {
+                return Err(NaelError::InvalidArchive(format!(
+                    "archive entry {name:?} has an unsupported type {entry_type:?}"
+                ))
+                .into());
+            }
+        }
+
+        on_entry(&relative_path, entry_size);
+    }
+
+    Ok(())
+}
+
+/// Writes the entries of an archive to a destination path, validating every entry and enforcing the limits across all
+/// of them.
+struct EntryWriter<'a> {
+    destination_path: &'a Path,
+    limits: ExtractLimits,
+    remaining_size: u64,
+    remaining_entries: u64,
+}
+
+impl<'a> EntryWriter<'a> {
+    /// Create a writer for the given destination path, creating it if it doesn't exist.
+    fn new(destination_path: &'a Path, limits: ExtractLimits) -> Result<Self> {
+        fs::create_dir_all(destination_path)
+            .with_context(|| format!("failed to create directory {destination_path:?}"))?;
+        Ok(Self {
+            destination_path,
+            limits,
+            remaining_size: limits.max_size,
+            remaining_entries: limits.max_entries,
+        })
+    }
+
+    /// Count a new entry against the limits and get its validated path relative to the destination path.
+    fn begin_entry(&mut self, name: &str) -> Result<PathBuf> {
+        self.remaining_entries = self.remaining_entries.checked_sub(1).ok_or_else(|| {
+            NaelError::InvalidArchive(format!(
+                "archive contains more entries than the limit of {}",
+                self.limits.max_entries
+            ))
+        })?;
+        validate_entry_path(name)
+    }
 
+    /// Wrap a reader so that it never reads more than one byte past the remaining size limit, no matter what size the
+    /// entry declares.
+    fn limit_reader<R: Read>(&self, reader: R) -> io::Take<R> {
+        reader.take(self.remaining_size.saturating_add(1))
+    }
+
+    /// Count the given amount of written bytes against the size limit.
+    fn consume_size(&mut self, size: u64) -> Result<()> {
+        self.remaining_size = self.remaining_size.checked_sub(size).ok_or_else(|| {
+            NaelError::InvalidArchive(format!(
+                "archive decompresses to more than the limit of {} bytes",
+                self.limits.max_size
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Create a directory entry.
+    fn write_directory(&self, relative_path: &Path) -> Result<()> {
+        self.validate_no_symlinks(relative_path)?;
+        let path = self.destination_path.join(relative_path);
+        fs::create_dir_all(&path).with_context(|| format!("failed to create directory {path:?}"))
+    }
+
+    /// Write a file entry with the contents of the given reader, setting its permissions to the given unix mode.
+    fn write_file<R: Read>(
+        &mut self,
+        relative_path: &Path,
+        reader: R,
+        #[cfg_attr(not(unix), allow(unused_variables))] mode: Option<u32>,
+    ) -> Result<()> {
+        let path = self.create_parent(relative_path)?;
+        let mut file =
+            File::create(&path).with_context(|| format!("failed to create file {path:?}"))?;
+        let size = io::copy(&mut self.limit_reader(reader), &mut file)
+            .with_context(|| format!("failed to extract {path:?}"))?;
+        self.consume_size(size)?;
+
+        #[cfg(unix)]
+        if let Some(mode) = mode {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode))
+                .with_context(|| format!("failed to set permissions of {path:?}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Create a symlink entry pointing to the given target, which must stay inside of the destination path.
+    fn write_symlink(&mut self, relative_path: &Path, target: &str) -> Result<()> {
+        validate_symlink_target(relative_path, target)?;
+        self.consume_size(target.len() as u64)?;
+        let path = self.create_parent(relative_path)?;
+        symlink::symlink_auto(target, &path)
+            .with_context(|| format!("failed to create symlink {path:?}"))
+    }
+
+    /// Create the parent directories of an entry and get its full path.
+    fn create_parent(&self, relative_path: &Path) -> Result<PathBuf> {
+        if relative_path.as_os_str().is_empty() {
+            return Err(NaelError::InvalidArchive(
+                "archive contains a file entry with an empty path".to_owned(),
+            )
+            .into());
+        }
+        self.validate_no_symlinks(relative_path)?;
+        let path = self.destination_path.join(relative_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory {parent:?}"))?;
+        }
+        Ok(path)
+    }
+
+    /// Check that no part of the path of an entry is a symlink that was extracted by an earlier entry, as writing through
+    /// it could place the entry outside of the destination path even when every symlink target is valid on its own.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When the path of the entry, or any of its parents, is a symlink.
+    /// * When unable to read the metadata of any part of the path.
+    fn validate_no_symlinks(&self, relative_path: &Path) -> Result<()> {
+        let mut path = self.destination_path.to_path_buf();
+        for component in relative_path.components() {
+            path.push(component);
+            match fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.file_type().is_symlink() => {
+                    return Err(NaelError::InvalidArchive(format!(
+                        "archive entry {relative_path:?} would be written through the symlink {path:?}"
+                    ))
+                    .into());
+                }
+                Ok(_) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => break,
+                Err(err) => {
+                    return Err(err)
+                        .with_context(|| format!("failed to read metadata of {path:?}"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Get the total uncompressed size that every entry inside of a zip archive declares.
+fn get_uncompressed_size<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<u64> {
+    let mut size: u64 = 0;
+    for index in 0..archive.len() {
+        size = size.saturating_add(
+            archive
+                .by_index_raw(index)
+                .with_context(|| format!("failed to read entry {index} of archive"))?
+                .size(),
+        );
+    }
+    Ok(size)
+}
+
+/// Convert the name of an archive entry into a path relative to the extraction destination, which is empty for the
+/// destination itself (e.g. `./`).
+///
+/// Both forward and backward slashes are treated as separators, as archives created on Windows may use either.
+///
+/// # Errors
+/// This function will return an error in the following situations, but is not limited to just these cases:
+/// * When the name is absolute or contains a `..` component.
+fn validate_entry_path(name: &str) -> Result<PathBuf> {
+    if is_absolute(name) {
+        return Err(NaelError::InvalidArchive(format!(
+            "archive entry {name:?} has an absolute path"
+        ))
+        .into());
+    }
+    let mut path = PathBuf::new();
+    for part in name.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                return Err(NaelError::InvalidArchive(format!(
+                    "archive entry {name:?} has a path outside of the destination"
+                ))
+                .into());
+            }
+            part => path.push(part),
+        }
+    }
+    Ok(path)
+}
+
+/// Check that the target of a symlink entry at the given relative path stays inside of the extraction destination.
+///
+/// # Errors
+/// This function will return an error in the following situations, but is not limited to just these cases:
+/// * When the target is absolute or would resolve to a path outside of the destination.
+fn validate_symlink_target(relative_path: &Path, target: &str) -> Result<()> {
+    if is_absolute(target) {
+        return Err(NaelError::InvalidArchive(format!(
+            "symlink {relative_path:?} has an absolute target {target:?}"
+        ))
+        .into());
+    }
+    // The amount of directories between the destination and the current position while following the target.
+    let mut depth = relative_path.components().count().saturating_sub(1);
+    for part in target.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                depth = depth.checked_sub(1).ok_or_else(|| {
+                    NaelError::InvalidArchive(format!(
+                        "symlink {relative_path:?} points outside of the destination ({target:?})"
+                    ))
+                })?;
+            }
+            _ => depth += 1,
+        }
+    }
     Ok(())
 }
+
+/// Check whether an archive path is absolute on any platform, as archives may have been created on another one.
+///
+/// Any path containing a `:` is treated as absolute to catch drive letters (e.g. `C:`) and other Windows prefixes.
+fn is_absolute(path: &str) -> bool {
+    path.starts_with(['/', '\\']) || path.contains(':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use tempfile::TempDir;
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    const LIMITS: ExtractLimits = ExtractLimits {
+        max_size: 1024 * 1024,
+        max_entries: 100,
+    };
+
+    /// An entry of a crafted archive, written exactly as given without any validation.
+    enum Entry {
+        File(&'static str, &'static [u8]),
+        Directory(&'static str),
+        Symlink(&'static str, &'static str),
+    }
+
+    /// The entries of an archive that tries to escape the destination by writing through a chain of symlinks which
+    /// each stay inside of the destination on their own.
+    const CHAINED_SYMLINK_ENTRIES: &[Entry] = &[
+        Entry::Symlink("x/s1", ".."),
+        Entry::Symlink("x/s1/s2", "../.."),
+        Entry::File("x/s1/s2/ESCAPED.txt", b"escaped"),
+    ];
+
+    fn build_zip(entries: &[Entry]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        for entry in entries {
+            match entry {
+                Entry::File(name, contents) => {
+                    writer.start_file(*name, options).unwrap();
+                    writer.write_all(contents).unwrap();
+                }
+                Entry::Directory(name) => writer.add_directory(*name, options).unwrap(),
+                Entry::Symlink(name, target) => {
+                    writer.add_symlink(*name, *target, options).unwrap()
+                }
+            }
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn build_tar(entries: &[Entry]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for entry in entries {
+            let mut header = tar::Header::new_old();
+            let (name, contents): (&str, &[u8]) = match entry {
+                Entry::File(name, contents) => {
+                    header.set_entry_type(EntryType::Regular);
+                    (name, contents)
+                }
+                Entry::Directory(name) => {
+                    header.set_entry_type(EntryType::Directory);
+                    (name, &[])
+                }
+                Entry::Symlink(name, target) => {
+                    header.set_entry_type(EntryType::Symlink);
+                    header.as_old_mut().linkname[..target.len()].copy_from_slice(target.as_bytes());
+                    (name, &[])
+                }
+            };
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_mode(0o644);
+            header.set_size(contents.len() as u64);
+            header.set_cksum();
+            builder.append(&header, contents).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn extract_zip_bytes(bytes: Vec<u8>, destination: &Path, limits: ExtractLimits) -> Result<()> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+        extract_zip(&mut archive, &destination, limits, |_, _| {})
+    }
+
+    fn extract_tar_bytes(bytes: Vec<u8>, destination: &Path, limits: ExtractLimits) -> Result<()> {
+        extract_tar(Cursor::new(bytes), &destination, limits, |_, _| {})
+    }
+
+    /// Extract the entries as both a zip and a tar archive, each into its own destination inside of a new temporary
+    /// directory, and check that both are rejected as invalid archives.
+    fn assert_rejected(entries: &[Entry], limits: ExtractLimits) -> TempDir {
+        let directory = TempDir::new().unwrap();
+        for (format, result) in [
+            (
+                "zip",
+                extract_zip_bytes(build_zip(entries), &directory.path().join("zip"), limits),
+            ),
+            (
+                "tar",
+                extract_tar_bytes(build_tar(entries), &directory.path().join("tar"), limits),
+            ),
+        ] {
+            let err = result.expect_err(&format!("{format} archive should be rejected"));
+            assert!(
+                matches!(err.downcast_ref(), Some(NaelError::InvalidArchive(_))),
+                "{format} archive was rejected with an unexpected error: {err:#}"
+            );
+        }
+        directory
+    }
+
+    #[test]
+    fn extracts_valid_archives() {
+        let entries = &[
+            Entry::Directory("plugins/"),
+            Entry::File("plugins/Dalamud.dll", b"dalamud"),
+            Entry::File("nested\\windows.txt", b"windows"),
+        ];
+        let directory = TempDir::new().unwrap();
+        extract_zip_bytes(build_zip(entries), &directory.path().join("zip"), LIMITS).unwrap();
+        extract_tar_bytes(build_tar(entries), &directory.path().join("tar"), LIMITS).unwrap();
+
+        for destination in ["zip", "tar"] {
+            let destination = directory.path().join(destination);
+            assert_eq!(
+                fs::read(destination.join("plugins/Dalamud.dll")).unwrap(),
+                b"dalamud"
+            );
+            assert_eq!(
+                fs::read(destination.join("nested/windows.txt")).unwrap(),
+                b"windows"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn extracts_symlinks_inside_of_destination() {
+        let entries = &[
+            Entry::File("lib/Dalamud.dll", b"dalamud"),
+            Entry::Symlink("bin/Dalamud.dll", "../lib/Dalamud.dll"),
+        ];
+        let directory = TempDir::new().unwrap();
+        extract_zip_bytes(build_zip(entries), &directory.path().join("zip"), LIMITS).unwrap();
+        extract_tar_bytes(build_tar(entries), &directory.path().join("tar"), LIMITS).unwrap();
+
+        for destination in ["zip", "tar"] {
+            let path = directory.path().join(destination).join("bin/Dalamud.dll");
+            assert!(
+                fs::symlink_metadata(&path)
+                    .unwrap()
+                    .file_type()
+                    .is_symlink()
+            );
+            assert_eq!(fs::read(path).unwrap(), b"dalamud");
+        }
+    }
+
+    #[test]
+    fn rejects_absolute_paths() {
+        assert_rejected(&[Entry::File("/etc/evil.txt", b"evil")], LIMITS);
+        assert_rejected(&[Entry::File("C:\\evil.txt", b"evil")], LIMITS);
+    }
+
+    #[test]
+    fn rejects_parent_components() {
+        let directory = assert_rejected(&[Entry::File("a/../../evil.txt", b"evil")], LIMITS);
+        assert!(!directory.path().join("evil.txt").exists());
+    }
+
+    #[test]
+    fn rejects_backslash_parent_components() {
+        let directory = assert_rejected(&[Entry::File("a\\..\\..\\evil.txt", b"evil")], LIMITS);
+        assert!(!directory.path().join("evil.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_outside_of_destination() {
+        assert_rejected(&[Entry::Symlink("link", "../outside")], LIMITS);
+        assert_rejected(&[Entry::Symlink("link", "/etc")], LIMITS);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_entries_written_through_symlinks() {
+        let directory = assert_rejected(CHAINED_SYMLINK_ENTRIES, LIMITS);
+        assert!(!directory.path().join("ESCAPED.txt").exists());
+        assert!(
+            !directory
+                .path()
+                .parent()
+                .unwrap()
+                .join("ESCAPED.txt")
+                .exists()
+        );
+    }
+
+    #[test]
+    fn rejects_too_many_entries() {
+        assert_rejected(
+            &[
+                Entry::File("a.txt", b"a"),
+                Entry::File("b.txt", b"b"),
+                Entry::File("c.txt", b"c"),
+            ],
+            ExtractLimits {
+                max_entries: 2,
+                ..LIMITS
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_declared_size_over_limit() {
+        let directory = TempDir::new().unwrap();
+        let err = extract_zip_bytes(
+            build_zip(&[Entry::File("large.bin", &[0; 64])]),
+            directory.path(),
+            ExtractLimits {
+                max_size: 32,
+                ..LIMITS
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(NaelError::InvalidArchive(_))
+        ));
+        // The declared size is checked before anything is extracted.
+        assert!(!directory.path().join("large.bin").exists());
+    }
+
+    #[test]
+    fn rejects_actual_size_over_limit() {
+        assert_rejected(
+            &[Entry::File("large.bin", &[0; 64])],
+            ExtractLimits {
+                max_size: 32,
+                ..LIMITS
+            },
+        );
+
+        // A zip entry that declares a smaller uncompressed size than it has must be caught while extracting.
+        let mut bytes = build_zip(&[Entry::File("large.bin", &[0; 64])]);
+        understate_zip_sizes(&mut bytes, 1);
+        let directory = TempDir::new().unwrap();
+        let err = extract_zip_bytes(
+            bytes,
+            directory.path(),
+            ExtractLimits {
+                max_size: 32,
+                ..LIMITS
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(NaelError::InvalidArchive(_))
+        ));
+    }
+
+    /// Overwrite the uncompressed size declared by every local and central directory header of a zip archive.
+    fn understate_zip_sizes(bytes: &mut [u8], size: u32) {
+        const LOCAL_HEADER: &[u8] = b"PK\x03\x04";
+        const CENTRAL_HEADER: &[u8] = b"PK\x01\x02";
+        for offset in 0..bytes.len() - 4 {
+            let size_offset = match &bytes[offset..offset + 4] {
+                LOCAL_HEADER => offset + 22,
+                CENTRAL_HEADER => offset + 24,
+                _ => continue,
+            };
+            bytes[size_offset..size_offset + 4].copy_from_slice(&size.to_le_bytes());
+        }
+    }
+}
@@ -0,0 +1,415 @@
+use super::checksum;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The file extension of cached release archives.
+const ARCHIVE_EXTENSION: &str = "archive";
+
+/// The file extension of the metadata stored alongside each cached release archive.
+const METADATA_EXTENSION: &str = "json";
+
+/// The file extension of archives that are still being copied into the cache.
+const TEMPORARY_EXTENSION: &str = "tmp";
+
+/// The name of the sub-directory that contains in-progress downloads, kept so interrupted downloads can be resumed.
+const DOWNLOADS_DIRNAME: &str = "downloads";
+
+/// How long a partial download is kept for resuming before it is considered abandoned and removed when pruning.
+const PARTIAL_DOWNLOAD_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Information about a single release archive stored inside of an [`ArchiveCache`].
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    /// The SHA-256 hash of the archive as a lowercase hex string, which is also the key of the entry.
+    pub sha256: String,
+    /// The size of the archive in bytes.
+    pub size: u64,
+    /// The assembly version of the release the archive contains, if it was known when cached.
+    pub assembly_version: Option<String>,
+    /// The git sha of the release the archive contains, if it was known when cached.
+    pub git_sha: Option<String>,
+    /// The time the archive was last used, in milliseconds since the Unix epoch.
+    pub last_used: u128,
+}
+
+/// A content-addressed cache of downloaded release archives, shared by every branch installation.
+///
+/// Archives are keyed by their SHA-256 hash and can additionally be looked up by the version of the release they contain.
+/// The least recently used archives are evicted whenever the total size of the cache exceeds its size limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveCache {
+    directory: PathBuf,
+    size_limit: u64,
+}
+
+impl ArchiveCache {
+    /// Create a cache that stores archives inside of the given directory, limited to the given amount of bytes.
+    ///
+    /// A size limit of `0` disables storing new archives, though existing archives can still be listed and removed.
+    pub fn new<P: AsRef<Path>>(directory: &P, size_limit: u64) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            size_limit,
+        }
+    }
+
+    /// Get the maximum total size of the cache in bytes.
+    pub fn size_limit(&self) -> u64 {
+        self.size_limit
+    }
+
+    /// Get the path of the archive with the given hash.
+    fn get_archive_path(&self, sha256: &str) -> PathBuf {
+        self.directory.join(format!("{sha256}.{ARCHIVE_EXTENSION}"))
+    }
+
+    /// Get the path of the metadata of the archive with the given hash.
+    fn get_metadata_path(&self, sha256: &str) -> PathBuf {
+        self.directory
+            .join(format!("{sha256}.{METADATA_EXTENSION}"))
+    }
+
+    /// Get the path that the archive at the given URL should be downloaded to before being inserted into the cache.
+    ///
+    /// The path is stable for each URL, so an interrupted download can be resumed by the next download of the same URL.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to create the downloads directory.
+    pub fn get_download_path(&self, url: &str) -> Result<PathBuf> {
+        let downloads_directory = self.directory.join(DOWNLOADS_DIRNAME);
+        fs::create_dir_all(&downloads_directory).with_context(|| {
+            format!("failed to create downloads directory {downloads_directory:?}")
+        })?;
+        Ok(downloads_directory.join(checksum::sha256_bytes(url.as_bytes())))
+    }
+
+    /// Get every entry inside of the cache, ordered from most to least recently used.
+    ///
+    /// Entries with missing archives or unreadable metadata are skipped.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to read the cache directory.
+    pub fn entries(&self) -> Result<Vec<CacheEntry>> {
+        if !self
+            .directory
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {:?}", self.directory))?
+        {
+            return Ok(vec![]);
+        }
+
+        let mut entries = fs::read_dir(&self.directory)
+            .with_context(|| format!("failed to read {:?}", self.directory))?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != METADATA_EXTENSION {
+                    return None;
+                }
+                let entry: CacheEntry =
+                    serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+                self.get_archive_path(&entry.sha256)
+                    .is_file()
+                    .then_some(entry)
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| Reverse(entry.last_used));
+
+        Ok(entries)
+    }
+
+    /// Get the total size of every archive inside of the cache and every partial download in bytes.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to read the cache directory.
+    pub fn total_size(&self) -> Result<u64> {
+        Ok(self.entries()?.iter().map(|entry| entry.size).sum::<u64>()
+            + self
+                .downloads()?
+                .iter()
+                .map(|(_, size, _)| size)
+                .sum::<u64>())
+    }
+
+    /// Get the path, size and last modification time of every file inside of the downloads directory, which are partial
+    /// downloads, their metadata and completed downloads that haven't been inserted yet.
+    fn downloads(&self) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let downloads_directory = self.directory.join(DOWNLOADS_DIRNAME);
+        if !downloads_directory
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {downloads_directory:?}"))?
+        {
+            return Ok(vec![]);
+        }
+
+        Ok(fs::read_dir(&downloads_directory)
+            .with_context(|| format!("failed to read {downloads_directory:?}"))?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let metadata = entry.metadata().ok()?;
+                if !metadata.is_file() {
+                    return None;
+                }
+                Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+            })
+            .collect())
+    }
+
+    /// Remove every download that hasn't been written to for [`PARTIAL_DOWNLOAD_MAX_AGE`], returning the total size of
+    /// the remaining downloads apart from the given download that is in use.
+    fn prune_downloads(&self, in_use: Option<&Path>) -> Result<u64> {
+        let mut total_size = 0;
+        for (path, size, modified) in self.downloads()? {
+            if in_use == Some(path.as_path()) {
+                continue;
+            }
+            if modified
+                .elapsed()
+                .is_ok_and(|age| age > PARTIAL_DOWNLOAD_MAX_AGE)
+            {
+                fs::remove_file(&path).with_context(|| format!("failed to delete {path:?}"))?;
+            } else {
+                total_size += size;
+            }
+        }
+        Ok(total_size)
+    }
+
+    /// Find a cached archive with the given SHA-256 hash, returning the path to it.
+    ///
+    /// The archive is re-hashed before being returned and discarded if it no longer matches its hash.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to read the cache directory.
+    /// * When unable to read or update the matching entry.
+    pub fn find_by_checksum(&self, sha256: &str) -> Result<Option<PathBuf>> {
+        let Some(entry) = self
+            .entries()?
+            .into_iter()
+            .find(|entry| entry.sha256 == sha256)
+        else {
+            return Ok(None);
+        };
+        self.use_entry(entry)
+    }
+
+    /// Find a cached archive containing the release with the given assembly version and git sha, returning the path to it.
+    ///
+    /// The archive is re-hashed before being returned and discarded if it no longer matches its hash.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to read the cache directory.
+    /// * When unable to read or update the matching entry.
+    pub fn find_by_version(
+        &self,
+        assembly_version: &str,
+        git_sha: Option<&str>,
+    ) -> Result<Option<PathBuf>> {
+        let Some(entry) = self.entries()?.into_iter().find(|entry| {
+            entry.assembly_version.as_deref() == Some(assembly_version)
+                && entry.git_sha.as_deref() == git_sha
+        }) else {
+            return Ok(None);
+        };
+        self.use_entry(entry)
+    }
+
+    /// Verify the archive of the given entry and mark it as used, discarding it if it has been corrupted.
+    fn use_entry(&self, mut entry: CacheEntry) -> Result<Option<PathBuf>> {
+        let archive_path = self.get_archive_path(&entry.sha256);
+        if checksum::sha256_file(&archive_path)? != entry.sha256 {
+            self.remove(&entry.sha256)?;
+            return Ok(None);
+        }
+
+        entry.last_used = now_millis()?;
+        self.write_metadata(&entry)?;
+        Ok(Some(archive_path))
+    }
+
+    /// Copy the archive at the given path into the cache, then evict the least recently used archives until the cache
+    /// fits inside of its size limit. Does nothing when the size limit is `0`.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to create the cache directory.
+    /// * When unable to copy the archive or write its metadata.
+    /// * When unable to evict an archive.
+    pub fn insert<P: AsRef<Path> + Debug>(
+        &self,
+        archive_path: &P,
+        sha256: &str,
+        assembly_version: Option<&str>,
+        git_sha: Option<&str>,
+    ) -> Result<()> {
+        if self.size_limit == 0 {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.directory)
+            .with_context(|| format!("failed to create cache directory {:?}", self.directory))?;
+        let cached_archive_path = self.get_archive_path(sha256);
+        let temporary_path = cached_archive_path.with_extension(TEMPORARY_EXTENSION);
+        let size = fs::copy(archive_path, &temporary_path)
+            .with_context(|| format!("failed to copy {archive_path:?} to {temporary_path:?}"))?;
+        fs::rename(&temporary_path, &cached_archive_path).with_context(|| {
+            format!("failed to move {temporary_path:?} to {cached_archive_path:?}")
+        })?;
+        self.write_metadata(&CacheEntry {
+            sha256: sha256.to_owned(),
+            size,
+            assembly_version: assembly_version.map(str::to_owned),
+            git_sha: git_sha.map(str::to_owned),
+            last_used: now_millis()?,
+        })?;
+
+        self.prune_impl(|_| false, Some(archive_path.as_ref()))?;
+        Ok(())
+    }
+
+    /// Remove every entry the given predicate returns `true` for, then evict the least recently used archives until the
+    /// cache fits inside of its size limit, returning the removed entries.
+    ///
+    /// Partial downloads that haven't been resumed for a week are removed as well, and the remaining ones count towards
+    /// the size limit.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to read the cache directory.
+    /// * When unable to remove an archive or partial download.
+    pub fn prune<F: Fn(&CacheEntry) -> bool>(&self, predicate: F) -> Result<Vec<CacheEntry>> {
+        self.prune_impl(predicate, None)
+    }
+
+    /// Prune the cache like [`ArchiveCache::prune`], without counting the given download that is in use towards the
+    /// size limit.
+    fn prune_impl<F: Fn(&CacheEntry) -> bool>(
+        &self,
+        predicate: F,
+        in_use_download: Option<&Path>,
+    ) -> Result<Vec<CacheEntry>> {
+        let mut removed = vec![];
+        let mut total_size = self.prune_downloads(in_use_download)?;
+        for entry in self.entries()? {
+            if predicate(&entry) || total_size + entry.size > self.size_limit {
+                self.remove(&entry.sha256)?;
+                removed.push(entry);
+            } else {
+                total_size += entry.size;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Remove the archive with the given hash from the cache, if it exists.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to delete the archive or its metadata.
+    pub fn remove(&self, sha256: &str) -> Result<()> {
+        for path in [
+            self.get_metadata_path(sha256),
+            self.get_archive_path(sha256),
+        ] {
+            if path
+                .try_exists()
+                .with_context(|| format!("unable to check existence of {path:?}"))?
+            {
+                fs::remove_file(&path).with_context(|| format!("failed to delete {path:?}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove every archive from the cache.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to delete the cache directory.
+    pub fn clear(&self) -> Result<()> {
+        if self
+            .directory
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {:?}", self.directory))?
+        {
+            fs::remove_dir_all(&self.directory)
+                .with_context(|| format!("failed to delete {:?}", self.directory))?;
+        }
+        Ok(())
+    }
+
+    /// Write the metadata of the given entry next to its archive.
+    fn write_metadata(&self, entry: &CacheEntry) -> Result<()> {
+        let metadata_path = self.get_metadata_path(&entry.sha256);
+        fs::write(&metadata_path, serde_json::to_string(entry)?)
+            .with_context(|| format!("failed to write {metadata_path:?}"))
+    }
+}
+
+/// Get the current time in milliseconds since the Unix epoch.
+fn now_millis() -> Result<u128> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::TempDir;
+
+    #[test]
+    fn prunes_abandoned_partial_downloads() {
+        let directory = TempDir::new().unwrap();
+        let cache = ArchiveCache::new(&directory.path(), 1024);
+        let recent_path = cache
+            .get_download_path("https://example.com/recent.zip")
+            .unwrap();
+        let abandoned_path = cache
+            .get_download_path("https://example.com/abandoned.zip")
+            .unwrap();
+        fs::write(&recent_path, [0; 16]).unwrap();
+        fs::write(&abandoned_path, [0; 32]).unwrap();
+        File::options()
+            .write(true)
+            .open(&abandoned_path)
+            .unwrap()
+            .set_modified(SystemTime::now() - PARTIAL_DOWNLOAD_MAX_AGE * 2)
+            .unwrap();
+        assert_eq!(cache.total_size().unwrap(), 48);
+
+        cache.prune(|_| false).unwrap();
+        assert!(recent_path.exists());
+        assert!(!abandoned_path.exists());
+        assert_eq!(cache.total_size().unwrap(), 16);
+    }
+
+    #[test]
+    fn partial_downloads_count_towards_size_limit() {
+        let directory = TempDir::new().unwrap();
+        let cache = ArchiveCache::new(&directory.path(), 64);
+        let archive_path = directory.path().join("release.zip");
+        fs::write(&archive_path, [0; 32]).unwrap();
+        cache.insert(&archive_path, "a", None, None).unwrap();
+        assert_eq!(cache.entries().unwrap().len(), 1);
+
+        let download_path = cache
+            .get_download_path("https://example.com/large.zip")
+            .unwrap();
+        fs::write(download_path, [0; 48]).unwrap();
+        let removed = cache.prune(|_| false).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert!(cache.entries().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::{Context, Result, anyhow};
+use sha2::{Digest, Sha256};
+use std::{fmt::Debug, fs::File, io, path::Path};
+
+/// The length of a hex encoded SHA-256 hash.
+const SHA256_HEX_LEN: usize = 64;
+
+/// Calculate the SHA-256 hash of the file at the given path as a lowercase hex string.
+///
+/// # Errors
+/// This function will return an error in the following situations, but is not limited to just these cases:
+/// * When unable to open the file at the given path for reading.
+/// * When reading the file fails.
+pub fn sha256_file<P: AsRef<Path> + Debug>(path: &P) -> Result<String> {
+    let mut file = File::open(path).with_context(|| format!("failed to open file {path:?}"))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).with_context(|| format!("failed to read file {path:?}"))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Calculate the SHA-256 hash of the given bytes as a lowercase hex string.
+pub fn sha256_bytes(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Parse a SHA-256 hash from the contents of a checksum file, normalizing it to lowercase.
+///
+/// Both files that only contain the hash and files in the `sha256sum` format (`<hash>  <filename>`) are supported.
+///
+/// # Errors
+/// This function will return an error in the following situations, but is not limited to just these cases:
+/// * When the contents do not start with a valid hex encoded SHA-256 hash.
+pub fn parse_sha256(contents: &str) -> Result<String> {
+    let hash = contents
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if hash.len() != SHA256_HEX_LEN || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("'{hash}' is not a valid SHA-256 hash"));
+    }
+    Ok(hash)
+}
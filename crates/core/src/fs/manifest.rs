@@ -0,0 +1,140 @@
+use super::checksum;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Debug, fs, path::Path};
+
+/// A record of every file inside of an extracted release, used to detect modified or missing files later.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Manifest {
+    /// The files of the release keyed by their path relative to the release root, using `/` as the separator.
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+/// A single file inside of a [`Manifest`].
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The SHA-256 hash of the file as a lowercase hex string.
+    pub sha256: String,
+}
+
+/// The differences between a [`Manifest`] and the directory it was compared against.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ManifestDiff {
+    /// Files that are in the manifest but not in the directory.
+    pub missing: Vec<String>,
+    /// Files that are in the directory but not in the manifest.
+    pub extra: Vec<String>,
+    /// Files whose size or hash differs from the manifest.
+    pub modified: Vec<String>,
+}
+
+impl ManifestDiff {
+    /// Check whether the directory matched the manifest exactly.
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.modified.is_empty()
+    }
+}
+
+impl Manifest {
+    /// Build a manifest of every file inside of the given directory, skipping any top-level files with an ignored name.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to read any directory or file inside of the given directory.
+    pub fn from_directory<P: AsRef<Path> + Debug>(directory: &P, ignored: &[&str]) -> Result<Self> {
+        let mut files = BTreeMap::new();
+        collect_files(directory.as_ref(), "", ignored, &mut files)?;
+        Ok(Self { files })
+    }
+
+    /// Read a manifest from the file at the given path.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When a failure occurs reading the file at the given path.
+    /// * When deserialization fails.
+    pub fn from_path_ref<P: AsRef<Path> + Debug>(path: &P) -> Result<Self> {
+        serde_json::from_str(
+            &fs::read_to_string(path).with_context(|| format!("failed read file at {path:?}"))?,
+        )
+        .with_context(|| format!("unable to deserialize file at {path:?}"))
+    }
+
+    /// Write the manifest to the file at the given path, overwriting it if it exists.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When a failure occurs writing the file at the given path.
+    pub fn write_to_path<P: AsRef<Path> + Debug>(&self, path: &P) -> Result<()> {
+        fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("failed to write {path:?}"))
+    }
+
+    /// Compare the manifest against the current contents of the given directory.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When unable to read any directory or file inside of the given directory.
+    pub fn compare_directory<P: AsRef<Path> + Debug>(
+        &self,
+        directory: &P,
+        ignored: &[&str],
+    ) -> Result<ManifestDiff> {
+        let current = Self::from_directory(directory, ignored)?;
+        let mut diff = ManifestDiff::default();
+
+        for (path, entry) in &self.files {
+            match current.files.get(path) {
+                Some(current_entry) if current_entry == entry => {}
+                Some(_) => diff.modified.push(path.clone()),
+                None => diff.missing.push(path.clone()),
+            }
+        }
+        diff.extra = current
+            .files
+            .into_keys()
+            .filter(|path| !self.files.contains_key(path))
+            .collect();
+
+        Ok(diff)
+    }
+}
+
+/// Recursively add every regular file inside of the directory to the given map, keyed by its path relative to the root.
+fn collect_files(
+    directory: &Path,
+    prefix: &str,
+    ignored: &[&str],
+    files: &mut BTreeMap<String, ManifestEntry>,
+) -> Result<()> {
+    for entry in fs::read_dir(directory).with_context(|| format!("failed to read {directory:?}"))? {
+        let entry = entry.with_context(|| format!("failed to read entry of {directory:?}"))?;
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if prefix.is_empty() && ignored.contains(&file_name.as_str()) {
+            continue;
+        }
+
+        let path = entry.path();
+        let relative_path = format!("{prefix}{file_name}");
+        let file_type = entry
+            .file_type()
+            .with_context(|| format!("failed to read file type of {path:?}"))?;
+        if file_type.is_dir() {
+            collect_files(&path, &format!("{relative_path}/"), ignored, files)?;
+        } else if file_type.is_file() {
+            files.insert(
+                relative_path,
+                ManifestEntry {
+                    size: entry
+                        .metadata()
+                        .with_context(|| format!("failed to read metadata of {path:?}"))?
+                        .len(),
+                    sha256: checksum::sha256_file(&path)?,
+                },
+            );
+        }
+    }
+    Ok(())
+}
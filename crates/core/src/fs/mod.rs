@@ -1,2 +1,5 @@
 pub(crate) mod archive;
+pub mod cache;
+pub(crate) mod checksum;
+pub mod manifest;
 pub mod storage;
@@ -6,12 +6,27 @@ use std::{
 };
 
 /// A storage implemenation that nael uses to manage its state and data.
-pub trait AppStorage: Clone {
+pub trait AppStorage: Clone + Send + Sync + 'static {
     /// Get the raw local version info filename.
     ///
     /// If you are using this for manually joining with the branch path you should use [`AppStorage::get_branch_version_info_path()`] instead.
     fn get_version_info_filename(&self) -> &str;
 
+    /// Get the raw local release archive checksum filename.
+    ///
+    /// If you are using this for manually joining with the branch path you should use [`AppStorage::get_branch_checksum_path()`] instead.
+    fn get_checksum_filename(&self) -> &str;
+
+    /// Get the raw local release manifest filename.
+    ///
+    /// If you are using this for manually joining with the branch path you should use [`AppStorage::get_branch_manifest_path()`] instead.
+    fn get_manifest_filename(&self) -> &str;
+
+    /// Get the raw local release source name filename.
+    ///
+    /// If you are using this for manually joining with the branch path you should use [`AppStorage::get_branch_source_path()`] instead.
+    fn get_source_filename(&self) -> &str;
+
     /// Get a [`PathBuf`] to the symlink that links to the active branch of Dalamud.
     ///
     /// This will automatically create all leading directories apart from the symlink.
@@ -51,6 +66,104 @@ pub trait AppStorage: Clone {
     /// * When there is no valid home directory found.
     /// * When creating any leading directory fails.
     fn get_branch_version_info_path(&self, branch_name: &str) -> Result<PathBuf>;
+
+    /// Get a [`PathBuf`] to where the SHA-256 checksum of the release archive is stored for the given branch.
+    ///
+    /// This will automatically create all missing directories apart from the branch directory.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When there is no valid home directory found.
+    /// * When creating any leading directory fails.
+    fn get_branch_checksum_path(&self, branch_name: &str) -> Result<PathBuf>;
+
+    /// Get a [`PathBuf`] to where the manifest of extracted release files is stored for the given branch.
+    ///
+    /// This will automatically create all missing directories apart from the branch directory.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When there is no valid home directory found.
+    /// * When creating any leading directory fails.
+    fn get_branch_manifest_path(&self, branch_name: &str) -> Result<PathBuf>;
+
+    /// Get a [`PathBuf`] to where the name of the release source the given branch was installed from is stored.
+    ///
+    /// This will automatically create all missing directories apart from the branch directory.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When there is no valid home directory found.
+    /// * When creating any leading directory fails.
+    fn get_branch_source_path(&self, branch_name: &str) -> Result<PathBuf>;
+
+    /// Get a [`PathBuf`] of the directory that contains previous builds of the given branch, kept when updating.
+    ///
+    /// This will automatically create all missing directories apart from the branch history directory itself.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When there is no valid home directory found.
+    /// * When creating any leading directory fails.
+    fn get_branch_history_directory(&self, branch_name: &str) -> Result<PathBuf>;
+
+    /// Get a [`PathBuf`] of the directory that contains cached release archives shared by every branch.
+    ///
+    /// This will automatically create all missing directories apart from the cache directory itself.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When there is no valid home directory found.
+    /// * When creating any leading directory fails.
+    fn get_cache_directory(&self) -> Result<PathBuf>;
+
+    /// Get a [`PathBuf`] to where the last remote version information response is cached for the given branch, used to
+    /// make conditional requests when checking for updates.
+    ///
+    /// This will automatically create all missing directories apart from the file itself.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When there is no valid home directory found.
+    /// * When creating any leading directory fails.
+    fn get_branch_remote_version_info_cache_path(&self, branch_name: &str) -> Result<PathBuf>;
+
+    /// Get a [`PathBuf`] to where the branch names of the release source with the given name are cached, used to complete
+    /// remote branch names without a network request every time.
+    ///
+    /// This will automatically create all missing directories apart from the file itself.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When there is no valid home directory found.
+    /// * When creating any leading directory fails.
+    fn get_remote_branches_cache_path(&self, source_name: &str) -> Result<PathBuf>;
+
+    /// Get a [`PathBuf`] to the user configuration file. The file itself is not guaranteed to exist.
+    ///
+    /// This will automatically create all leading directories apart from the file.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When there is no valid home directory found.
+    /// * When creating any leading directory fails.
+    fn get_config_file_path(&self) -> Result<PathBuf>;
+
+    /// Get the maximum amount of previous builds that should be kept for each branch.
+    ///
+    /// A value of `0` means previous builds are discarded when updating.
+    fn get_history_retention(&self) -> usize;
+
+    /// Get the maximum total size in bytes of the cached release archives.
+    ///
+    /// A value of `0` means release archives are not cached.
+    fn get_cache_size_limit(&self) -> u64;
+
+    /// Get the maximum total uncompressed size in bytes of a release archive that will be extracted.
+    fn get_extract_size_limit(&self) -> u64;
+
+    /// Get the maximum amount of entries of a release archive that will be extracted.
+    fn get_extract_entry_limit(&self) -> u64;
 }
 
 /// The name of the sub-directory that contains installed branches of Dalamud.
@@ -60,6 +173,38 @@ pub trait AppStorage: Clone {
 const DALAMUD_BRANCHES_DIRNAME: &str = "dalamud-branches";
 const DALAMUD_BRANCHES_DIRNAME_OLD: &str = "dalamud-versions"; // Temporary
 
+/// The name of the sub-directory that contains previous builds of installed branches.
+//
+//  Warning:
+//  Any changes to this will break compatibility with existing installations.
+const DALAMUD_HISTORY_DIRNAME: &str = "dalamud-history";
+
+/// The name of the sub-directory that contains cached release archives.
+const DALAMUD_CACHE_DIRNAME: &str = "dalamud-cache";
+
+/// The name of the sub-directory that contains cached remote version information responses.
+///
+/// This is kept outside of the release archive cache so that clearing the archive cache doesn't discard it.
+const REMOTE_VERSION_INFO_CACHE_DIRNAME: &str = "remote-version-info";
+
+/// The name of the sub-directory that contains the cached branch names of each release source.
+const REMOTE_BRANCHES_CACHE_DIRNAME: &str = "remote-branches";
+
+/// The name of the user configuration file.
+const CONFIG_FILENAME: &str = "config.toml";
+
+/// The amount of previous builds that are kept for each branch unless configured otherwise.
+const DEFAULT_HISTORY_RETENTION: usize = 3;
+
+/// The maximum total size of cached release archives in bytes unless configured otherwise.
+const DEFAULT_CACHE_SIZE_LIMIT: u64 = 512 * 1024 * 1024;
+
+/// The maximum total uncompressed size of a release archive in bytes unless configured otherwise.
+const DEFAULT_EXTRACT_SIZE_LIMIT: u64 = 2 * 1024 * 1024 * 1024;
+
+/// The maximum amount of entries of a release archive unless configured otherwise.
+const DEFAULT_EXTRACT_ENTRY_LIMIT: u64 = 100_000;
+
 /// The name of the symlink to the active branch of Dalamud
 //  Warning:
 //  Any changes to this will break compatibility with existing installations.
@@ -70,6 +215,21 @@ const ACTIVE_DALAMUD_VERSION_DIRNAME: &str = "active";
 //  Any changes to this will break compatibility with existing installations.
 const VERSIONINFO_FILENAME: &str = "release.versiondata";
 
+/// The name of the file containing the SHA-256 checksum of the release archive inside of every release locally.
+//  Warning:
+//  Any changes to this will break compatibility with existing installations.
+const CHECKSUM_FILENAME: &str = "release.sha256";
+
+/// The name of the file containing the manifest of extracted files inside of every release locally.
+//  Warning:
+//  Any changes to this will break compatibility with existing installations.
+const MANIFEST_FILENAME: &str = "release.manifest";
+
+/// The name of the file containing the name of the release source inside of every release locally that records one.
+//  Warning:
+//  Any changes to this will break compatibility with existing installations.
+const SOURCE_FILENAME: &str = "release.source";
+
 /// An operating system standards compliant disk-storage based implementation of [`AppStorage`].
 ///
 /// Follows these standards for each operating system:
@@ -81,6 +241,10 @@ pub struct CompliantDiskStorage {
     qualifier: &'static str,
     organization: &'static str,
     name: &'static str,
+    history_retention: usize,
+    cache_size_limit: u64,
+    extract_size_limit: u64,
+    extract_entry_limit: u64,
 }
 
 impl CompliantDiskStorage {
@@ -89,9 +253,37 @@ impl CompliantDiskStorage {
             qualifier,
             organization,
             name,
+            history_retention: DEFAULT_HISTORY_RETENTION,
+            cache_size_limit: DEFAULT_CACHE_SIZE_LIMIT,
+            extract_size_limit: DEFAULT_EXTRACT_SIZE_LIMIT,
+            extract_entry_limit: DEFAULT_EXTRACT_ENTRY_LIMIT,
         }
     }
 
+    /// Set the maximum amount of previous builds that are kept for each branch.
+    pub fn with_history_retention(mut self, history_retention: usize) -> Self {
+        self.history_retention = history_retention;
+        self
+    }
+
+    /// Set the maximum total size in bytes of the cached release archives.
+    pub fn with_cache_size_limit(mut self, cache_size_limit: u64) -> Self {
+        self.cache_size_limit = cache_size_limit;
+        self
+    }
+
+    /// Set the maximum total uncompressed size in bytes of a release archive that will be extracted.
+    pub fn with_extract_size_limit(mut self, extract_size_limit: u64) -> Self {
+        self.extract_size_limit = extract_size_limit;
+        self
+    }
+
+    /// Set the maximum amount of entries of a release archive that will be extracted.
+    pub fn with_extract_entry_limit(mut self, extract_entry_limit: u64) -> Self {
+        self.extract_entry_limit = extract_entry_limit;
+        self
+    }
+
     /// Convinence function for [`directories::ProjectDirs::from`].
     fn get_project_dir(&self) -> Result<ProjectDirs> {
         directories::ProjectDirs::from(self.qualifier, self.organization, self.name)
@@ -132,6 +324,18 @@ impl AppStorage for CompliantDiskStorage {
         VERSIONINFO_FILENAME
     }
 
+    fn get_checksum_filename(&self) -> &str {
+        CHECKSUM_FILENAME
+    }
+
+    fn get_manifest_filename(&self) -> &str {
+        MANIFEST_FILENAME
+    }
+
+    fn get_source_filename(&self) -> &str {
+        SOURCE_FILENAME
+    }
+
     fn get_active_branch_symlink(&self) -> Result<PathBuf> {
         let config_dir: PathBuf = self.get_app_config_dir()?;
         Ok(config_dir.join(ACTIVE_DALAMUD_VERSION_DIRNAME))
@@ -157,4 +361,67 @@ impl AppStorage for CompliantDiskStorage {
             .get_branch_directory(branch_name)?
             .join(self.get_version_info_filename()))
     }
+
+    fn get_branch_checksum_path(&self, branch_name: &str) -> Result<PathBuf> {
+        Ok(self
+            .get_branch_directory(branch_name)?
+            .join(self.get_checksum_filename()))
+    }
+
+    fn get_branch_manifest_path(&self, branch_name: &str) -> Result<PathBuf> {
+        Ok(self
+            .get_branch_directory(branch_name)?
+            .join(self.get_manifest_filename()))
+    }
+
+    fn get_branch_source_path(&self, branch_name: &str) -> Result<PathBuf> {
+        Ok(self
+            .get_branch_directory(branch_name)?
+            .join(self.get_source_filename()))
+    }
+
+    fn get_branch_history_directory(&self, branch_name: &str) -> Result<PathBuf> {
+        let history_dir: PathBuf = self.get_app_data_dir()?.join(DALAMUD_HISTORY_DIRNAME);
+        create_dir_all(&history_dir).context("history directory creation failed")?;
+        Ok(history_dir.join(branch_name))
+    }
+
+    fn get_cache_directory(&self) -> Result<PathBuf> {
+        Ok(self.get_app_data_dir()?.join(DALAMUD_CACHE_DIRNAME))
+    }
+
+    fn get_branch_remote_version_info_cache_path(&self, branch_name: &str) -> Result<PathBuf> {
+        let cache_dir = self
+            .get_app_data_dir()?
+            .join(REMOTE_VERSION_INFO_CACHE_DIRNAME);
+        create_dir_all(&cache_dir)
+            .context("remote version info cache directory creation failed")?;
+        Ok(cache_dir.join(format!("{branch_name}.json")))
+    }
+
+    fn get_remote_branches_cache_path(&self, source_name: &str) -> Result<PathBuf> {
+        let cache_dir = self.get_app_data_dir()?.join(REMOTE_BRANCHES_CACHE_DIRNAME);
+        create_dir_all(&cache_dir).context("remote branches cache directory creation failed")?;
+        Ok(cache_dir.join(format!("{source_name}.json")))
+    }
+
+    fn get_config_file_path(&self) -> Result<PathBuf> {
+        Ok(self.get_app_config_dir()?.join(CONFIG_FILENAME))
+    }
+
+    fn get_history_retention(&self) -> usize {
+        self.history_retention
+    }
+
+    fn get_cache_size_limit(&self) -> u64 {
+        self.cache_size_limit
+    }
+
+    fn get_extract_size_limit(&self) -> u64 {
+        self.extract_size_limit
+    }
+
+    fn get_extract_entry_limit(&self) -> u64 {
+        self.extract_entry_limit
+    }
 }
@@ -1,3 +1,5 @@
 pub mod dalamud;
+pub mod error;
 pub mod fs;
-mod net;
+pub mod net;
+pub mod progress;
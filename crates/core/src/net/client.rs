@@ -0,0 +1,278 @@
+use anyhow::{Context, Result, anyhow};
+use reqwest::{
+    Certificate, Client, Proxy, RequestBuilder, Response, StatusCode,
+    header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER},
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
+/// The user agent that is sent with every network request unless configured otherwise.
+const USER_AGENT: &str = concat!("nael_core/", env!("CARGO_PKG_VERSION"));
+
+/// The time allowed to establish a connection unless configured otherwise.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The time allowed between receiving two pieces of data unless configured otherwise.
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The amount of times a failed request is retried unless configured otherwise.
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// The delay before the first retry unless configured otherwise, which doubles for every following retry.
+const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// The longest delay that will ever be waited between two attempts, including ones requested with `Retry-After`.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+static SETTINGS: OnceLock<ClientSettings> = OnceLock::new();
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Settings for the shared client that is used for all network requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientSettings {
+    connect_timeout: Duration,
+    read_timeout: Duration,
+    max_retries: u32,
+    retry_base_delay: Duration,
+    proxy: Option<String>,
+    ca_bundle: Option<PathBuf>,
+    user_agent: Option<String>,
+    headers: BTreeMap<String, String>,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            proxy: None,
+            ca_bundle: None,
+            user_agent: None,
+            headers: BTreeMap::new(),
+        }
+    }
+}
+
+impl ClientSettings {
+    /// Create the default client settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the time allowed to establish a connection.
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Set the time allowed between receiving two pieces of data before a request is considered stalled.
+    pub fn with_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    /// Set the amount of times a request is retried after a connection failure, a timeout or a retryable status.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the delay before the first retry, which doubles for every following retry.
+    pub fn with_retry_base_delay(mut self, retry_base_delay: Duration) -> Self {
+        self.retry_base_delay = retry_base_delay;
+        self
+    }
+
+    /// Send every request through the proxy at the given URL, which may use the `http`, `https`, `socks5` or `socks5h`
+    /// scheme. When no proxy is set, the standard `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment variables are used.
+    pub fn with_proxy(mut self, proxy: &str) -> Self {
+        self.proxy = Some(proxy.to_owned());
+        self
+    }
+
+    /// Trust the PEM encoded certificates inside of the file at the given path in addition to the built-in root
+    /// certificates, such as the certificate of a proxy that intercepts TLS connections.
+    pub fn with_ca_bundle<P: AsRef<Path>>(mut self, ca_bundle: &P) -> Self {
+        self.ca_bundle = Some(ca_bundle.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set the user agent that is sent with every request.
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_owned());
+        self
+    }
+
+    /// Add a header that is sent with every request, replacing any header that was previously added with the same name.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Build a [`Client`] from the settings.
+    fn build_client(&self) -> Result<Client> {
+        let mut builder = Client::builder()
+            .user_agent(self.user_agent.as_deref().unwrap_or(USER_AGENT))
+            .connect_timeout(self.connect_timeout)
+            .read_timeout(self.read_timeout);
+
+        if let Some(proxy) = &self.proxy {
+            builder = builder
+                .proxy(Proxy::all(proxy).with_context(|| format!("invalid proxy url {proxy}"))?);
+        }
+
+        if let Some(ca_bundle) = &self.ca_bundle {
+            for certificate in read_ca_bundle(ca_bundle)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes())
+                    .with_context(|| format!("invalid header name '{name}'"))?,
+                HeaderValue::from_str(value)
+                    .with_context(|| format!("invalid value for header '{name}'"))?,
+            );
+        }
+
+        builder
+            .default_headers(headers)
+            .build()
+            .context("failed to build http client")
+    }
+
+    /// Get the amount of times a request is retried.
+    pub(crate) fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Get a randomly jittered delay to wait before the given retry attempt, starting at `0`.
+    pub(crate) fn retry_delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_RETRY_DELAY);
+        let delay_millis = delay.as_millis() as u64;
+        Duration::from_millis(fastrand::u64(delay_millis / 2..=delay_millis))
+    }
+}
+
+/// Set the settings of the shared client that is used for all network requests.
+///
+/// # Errors
+/// This function will return an error in the following situations, but is not limited to just these cases:
+/// * When the client has already been configured or used.
+pub fn configure_client(settings: ClientSettings) -> Result<()> {
+    if CLIENT.get().is_some() {
+        return Err(anyhow!(
+            "the http client cannot be configured after it has been used"
+        ));
+    }
+    SETTINGS
+        .set(settings)
+        .map_err(|_| anyhow!("the http client has already been configured"))
+}
+
+/// Get the settings of the shared client, falling back to the defaults when it hasn't been configured.
+pub(crate) fn settings() -> &'static ClientSettings {
+    SETTINGS.get_or_init(ClientSettings::default)
+}
+
+/// Get the shared [`Client`] that should be used for all network requests, building it on first use.
+///
+/// # Errors
+/// This function will return an error in the following situations, but is not limited to just these cases:
+/// * When the client fails to build (e.g. the TLS backend cannot be initialized).
+/// * When any of the configured settings are invalid, such as an unreadable CA bundle or a malformed proxy URL.
+pub(crate) fn client() -> Result<&'static Client> {
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = settings().build_client()?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+/// Send the given request, retrying with a jittered exponential backoff after connection failures, timeouts and
+/// responses with a retryable status.
+///
+/// The response of the last attempt is returned even if it has a retryable status, so the caller can report it.
+///
+/// # Errors
+/// This function will return an error in the following situations, but is not limited to just these cases:
+/// * When the request cannot be cloned for retrying (e.g. it has a streaming body).
+/// * When the last attempt fails to send.
+pub(crate) async fn send(request: RequestBuilder) -> Result<Response> {
+    let settings = settings();
+    let mut attempt = 0;
+    loop {
+        let result = request
+            .try_clone()
+            .context("request cannot be retried")?
+            .send()
+            .await;
+        let retry_delay = match &result {
+            Ok(response) if is_retryable_status(response.status()) => {
+                Some(get_retry_after(response).unwrap_or_else(|| settings.retry_delay(attempt)))
+            }
+            Ok(_) => None,
+            Err(err) if err.is_connect() || err.is_timeout() => Some(settings.retry_delay(attempt)),
+            Err(_) => None,
+        };
+
+        match retry_delay {
+            Some(retry_delay) if attempt < settings.max_retries => {
+                tokio::time::sleep(retry_delay).await;
+                attempt += 1;
+            }
+            _ => return Ok(result?),
+        }
+    }
+}
+
+/// Check whether a response with the given status is likely to succeed when retried.
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Get the delay requested by the `Retry-After` header of the response in seconds, capped at [`MAX_RETRY_DELAY`].
+fn get_retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds).min(MAX_RETRY_DELAY))
+}
+
+/// Read every PEM encoded certificate inside of the file at the given path.
+fn read_ca_bundle<P: AsRef<Path> + Debug>(path: &P) -> Result<Vec<Certificate>> {
+    let certificates = Certificate::from_pem_bundle(
+        &fs::read(path).with_context(|| format!("failed to read CA bundle at {path:?}"))?,
+    )
+    .with_context(|| format!("invalid CA bundle at {path:?}"))?;
+    if certificates.is_empty() {
+        return Err(anyhow!("CA bundle at {path:?} contains no certificates"));
+    }
+    Ok(certificates)
+}
@@ -1,3 +1,5 @@
+mod client;
 mod remote_resource;
 
-pub(crate) use remote_resource::RemoteResource;
+pub use client::{ClientSettings, configure_client};
+pub use remote_resource::RemoteResource;
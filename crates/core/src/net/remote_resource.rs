@@ -1,9 +1,27 @@
+use super::client;
+use crate::{
+    error::NaelError,
+    progress::{ProgressKind, ProgressReporter, ProgressTask},
+};
 use anyhow::{Context, Result, anyhow};
-use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::{
+    StatusCode, Url,
+    header::{
+        CONTENT_RANGE, ETAG, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE,
+        LAST_MODIFIED, RANGE,
+    },
+};
 use serde::{Deserialize, Serialize};
-use std::{fmt::Debug, fs::File, io::Write, path::Path};
+use std::{
+    fmt::Debug,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 /// Represents a remote resource with convinence methods attached.
+///
+/// Resources with a `file://` URL are read directly from the local filesystem.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub struct RemoteResource {
     pub url: String,
@@ -15,62 +33,535 @@ impl RemoteResource {
         Self { url }
     }
 
+    /// Create a new resource from the given local file path as a `file://` URL.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When the path cannot be made absolute.
+    pub fn from_path<P: AsRef<Path> + Debug>(path: &P) -> Result<Self> {
+        let path = fs::canonicalize(path).with_context(|| format!("failed to resolve {path:?}"))?;
+        let url = Url::from_file_path(&path)
+            .map_err(|_| anyhow!("unable to convert {path:?} to a file url"))?;
+        Ok(Self {
+            url: url.to_string(),
+        })
+    }
+
+    /// Get the local filesystem path of the resource if it has a `file://` URL.
+    fn get_local_path(&self) -> Option<PathBuf> {
+        Url::parse(&self.url)
+            .ok()
+            .filter(|url| url.scheme() == "file")
+            .and_then(|url| url.to_file_path().ok())
+    }
+
     /// Read the entire contents of the resource to a [`String`].
     ///
     /// # Errors
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When a network request fails.
+    /// * When the server responds with a non-success status.
+    /// * When reading a local file fails.
     pub async fn read_to_string(&self) -> Result<String> {
-        let download = reqwest::get(&self.url)
+        if let Some(local_path) = self.get_local_path() {
+            return fs::read_to_string(&local_path)
+                .with_context(|| format!("failed to read local file at {local_path:?}"));
+        }
+
+        let download = client::send(client::client()?.get(&self.url))
             .await
             .with_context(|| format!("failed to read remote file at {}", self.url))?;
+        if !download.status().is_success() {
+            return Err(NaelError::HttpStatus {
+                url: self.url.clone(),
+                status: download.status().as_u16(),
+            }
+            .into());
+        }
         Ok(download.text().await?)
     }
 
+    /// Read the entire contents of the resource to a [`String`], using a conditional request to reuse the contents
+    /// stored at the given cache path when the resource hasn't changed since it was last read.
+    ///
+    /// The cache is validated with the `ETag` or `Last-Modified` headers of the previous response, a server that sends
+    /// neither is always read in full.
+    ///
+    /// # Errors
+    /// This function will return an error in the following situations, but is not limited to just these cases:
+    /// * When a network request fails.
+    /// * When the server responds with a non-success status.
+    /// * When reading a local file fails.
+    /// * When writing the cache fails.
+    pub async fn read_to_string_cached<P: AsRef<Path> + Debug>(
+        &self,
+        cache_path: &P,
+    ) -> Result<String> {
+        if self.get_local_path().is_some() {
+            return self.read_to_string().await;
+        }
+
+        let cached =
+            CachedResponse::from_path(cache_path.as_ref()).filter(|cached| cached.url == self.url);
+        let mut request = client::client()?.get(&self.url);
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = client::send(request)
+            .await
+            .with_context(|| format!("failed to read remote file at {}", self.url))?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                return Ok(cached.body);
+            }
+        }
+        if !response.status().is_success() {
+            return Err(NaelError::HttpStatus {
+                url: self.url.clone(),
+                status: response.status().as_u16(),
+            }
+            .into());
+        }
+
+        let get_header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::to_owned)
+        };
+        let etag = get_header(ETAG);
+        let last_modified = get_header(LAST_MODIFIED);
+        let body = response.text().await?;
+        if etag.is_some() || last_modified.is_some() {
+            CachedResponse {
+                url: self.url.clone(),
+                etag,
+                last_modified,
+                body: body.clone(),
+            }
+            .write_to_path(cache_path.as_ref())?;
+        } else {
+            let _ = fs::remove_file(cache_path);
+        }
+        Ok(body)
+    }
+
     /// Download the resource at the underlying url and write it to the disk at given path.
     ///
-    /// This method will output a progress bar to stderr.
+    /// Progress is sent to the given reporter, local files are copied without reporting any.
+    ///
+    /// # Notes
+    /// Data is written to `{path}.part` while downloading and only moved to the given path once complete. If a previous
+    /// download of the same resource was interrupted it is resumed with a `Range` request, as long as the server supports
+    /// it and the `ETag` or `Last-Modified` validator of the resource hasn't changed - otherwise it starts from scratch.
+    /// A partial download that already has the full length of the resource is used as is.
     ///
     /// # Errors
     /// This function will return an error in the following situations, but is not limited to just these cases:
     /// * When a network request fails.
     /// * When any filesystem operation fails.
-    pub async fn download_with_progress_bar<P: AsRef<Path> + Debug>(
+    pub async fn download_with_progress<P: AsRef<Path> + Debug>(
         &self,
         path: P,
+        reporter: &dyn ProgressReporter,
     ) -> Result<File> {
-        let mut download = reqwest::get(&self.url).await?;
-
-        let download_progress_bar = ProgressBar::new(download.content_length().unwrap_or(0));
-        download_progress_bar.set_style(
-            ProgressStyle::default_bar()
-                .template(
-                    "[{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta}) {msg}",
-                )
-                .unwrap()
-                .progress_chars("#>-"),
-        );
-        download_progress_bar.set_message(self.url.clone());
+        if let Some(local_path) = self.get_local_path() {
+            fs::copy(&local_path, &path)
+                .with_context(|| format!("failed to copy {local_path:?} to {path:?}"))?;
+            return File::open(&path).with_context(|| format!("failed to open file {path:?}"));
+        }
+
+        let part_path = append_extension(path.as_ref(), PART_EXTENSION);
+        let part_metadata_path = append_extension(&part_path, PART_METADATA_EXTENSION);
+
+        // Connection failures before the response are retried when sending, this retries responses that are cut off
+        // part way through by resuming from the data that has already been written.
+        let settings = client::settings();
+        let mut attempt = 0;
+        loop {
+            match self
+                .download_part_with_progress(&part_path, &part_metadata_path, reporter)
+                .await
+            {
+                Ok(()) => break,
+                Err(err) if attempt < settings.max_retries() && is_interrupted_body(&err) => {
+                    tokio::time::sleep(settings.retry_delay(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        fs::rename(&part_path, &path)
+            .with_context(|| format!("failed to move {part_path:?} to {path:?}"))?;
+        discard_partial_download(&part_path, &part_metadata_path);
+
+        File::open(&path).with_context(|| format!("failed to open file {path:?}"))
+    }
 
+    /// Download the resource into the given part file, resuming a previous partial download when possible.
+    async fn download_part_with_progress(
+        &self,
+        part_path: &Path,
+        part_metadata_path: &Path,
+        reporter: &dyn ProgressReporter,
+    ) -> Result<()> {
+        let mut resume_from = self.get_resumable_length(part_path, part_metadata_path)?;
+
+        let mut download = loop {
+            let mut request = client::client()?.get(&self.url);
+            if let Some((length, validator)) = &resume_from {
+                request = request
+                    .header(RANGE, format!("bytes={length}-"))
+                    .header(IF_RANGE, validator);
+            }
+            let download = client::send(request)
+                .await
+                .with_context(|| format!("failed to download remote file at {}", self.url))?;
+
+            // A previous download can finish writing the part file but stop before it is moved into place, resuming it
+            // then asks for a range past the end of the resource. The part file is complete if it has the same length as
+            // the resource, otherwise it is discarded and downloaded again from the start.
+            if download.status() == StatusCode::RANGE_NOT_SATISFIABLE
+                && let Some((length, _)) = &resume_from
+            {
+                if get_content_range_length(download.headers()) == Some(*length) {
+                    return Ok(());
+                }
+                discard_partial_download(part_path, part_metadata_path);
+                resume_from = None;
+                continue;
+            }
+            break download;
+        };
         if !download.status().is_success() {
-            download_progress_bar.finish_and_clear();
-            return Err(anyhow!(
-                "Network request to {} failed: {}",
-                self.url,
-                download.status()
-            ));
+            if resume_from.is_some() {
+                discard_partial_download(part_path, part_metadata_path);
+            }
+            return Err(NaelError::HttpStatus {
+                url: self.url.clone(),
+                status: download.status().as_u16(),
+            }
+            .into());
         }
 
-        let mut file =
-            File::create(&path).with_context(|| format!("failed creating file {path:?}",))?;
+        // The server only honours the range when the resource is unchanged, otherwise the full resource is sent.
+        let resumed_length = match &resume_from {
+            Some((length, _)) if download.status() == StatusCode::PARTIAL_CONTENT => {
+                if get_content_range_start(download.headers()) != Some(*length) {
+                    discard_partial_download(part_path, part_metadata_path);
+                    return Err(anyhow!(
+                        "Network request to {} returned an unexpected range, the partial download has been discarded",
+                        self.url
+                    ));
+                }
+                *length
+            }
+            _ => 0,
+        };
+        let mut file = if resumed_length > 0 {
+            OpenOptions::new()
+                .append(true)
+                .open(part_path)
+                .with_context(|| format!("failed opening file {part_path:?}"))?
+        } else {
+            PartialDownload {
+                url: self.url.clone(),
+                validator: get_validator(download.headers()),
+            }
+            .write_to_path(part_metadata_path)?;
+            File::create(part_path)
+                .with_context(|| format!("failed creating file {part_path:?}"))?
+        };
+
+        let mut progress = ProgressTask::start(
+            reporter,
+            ProgressKind::Download,
+            &self.url,
+            download
+                .content_length()
+                .map(|length| length + resumed_length),
+            None,
+        );
+        progress.set_position(resumed_length);
 
         while let Some(chunk) = download.chunk().await.context("failed to chunk download")? {
             file.write_all(&chunk)
                 .context("writing chunk to disk failed")?;
-            download_progress_bar.inc(chunk.len() as u64);
+            progress.inc(chunk.len() as u64);
+        }
+        progress.finish();
+
+        Ok(())
+    }
+
+    /// Get the length and validator of a previous partial download of this resource, if it can be resumed.
+    fn get_resumable_length(
+        &self,
+        part_path: &Path,
+        part_metadata_path: &Path,
+    ) -> Result<Option<(u64, String)>> {
+        if !part_path
+            .try_exists()
+            .with_context(|| format!("unable to check existence of {part_path:?}"))?
+        {
+            return Ok(None);
+        }
+
+        let Some(PartialDownload {
+            url,
+            validator: Some(validator),
+        }) = PartialDownload::from_path(part_metadata_path)
+        else {
+            return Ok(None);
+        };
+        if url != self.url {
+            return Ok(None);
         }
-        download_progress_bar.finish_and_clear();
 
-        Ok(file)
+        let length = fs::metadata(part_path)
+            .with_context(|| format!("failed to read metadata of {part_path:?}"))?
+            .len();
+        Ok((length > 0).then_some((length, validator)))
+    }
+}
+
+/// The extension appended to the path of a download while it is in progress.
+const PART_EXTENSION: &str = "part";
+
+/// The extension appended to the path of a partial download for its [`PartialDownload`] metadata.
+const PART_METADATA_EXTENSION: &str = "json";
+
+/// Metadata stored alongside a partial download so it can be safely resumed later.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+struct PartialDownload {
+    /// The URL the partial download was made from.
+    url: String,
+    /// The strong `ETag` or the `Last-Modified` date of the resource when the download was started.
+    validator: Option<String>,
+}
+
+impl PartialDownload {
+    /// Read the metadata at the given path, returning [`None`] when it is missing or invalid.
+    fn from_path(path: &Path) -> Option<Self> {
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
+
+    /// Write the metadata to the given path, overwriting it if it exists.
+    fn write_to_path(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("failed to write {path:?}"))
+    }
+}
+
+/// A response stored by [`RemoteResource::read_to_string_cached`] alongside the validators needed to revalidate it.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+struct CachedResponse {
+    /// The URL the response was read from.
+    url: String,
+    /// The `ETag` header of the response.
+    etag: Option<String>,
+    /// The `Last-Modified` header of the response.
+    last_modified: Option<String>,
+    /// The body of the response.
+    body: String,
+}
+
+impl CachedResponse {
+    /// Read the response at the given path, returning [`None`] when it is missing or invalid.
+    fn from_path(path: &Path) -> Option<Self> {
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
+
+    /// Write the response to the given path, overwriting it if it exists.
+    fn write_to_path(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("failed to write {path:?}"))
+    }
+}
+
+/// Get the value that can be used in an `If-Range` header to make sure a resumed download is of the same resource.
+///
+/// Weak entity tags cannot be used with `If-Range`, so the `Last-Modified` date is used instead when there is one.
+fn get_validator(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| {
+            headers
+                .get(LAST_MODIFIED)
+                .and_then(|last_modified| last_modified.to_str().ok())
+        })
+        .map(str::to_owned)
+}
+
+/// Get the first byte position of a `Content-Range` header in the `bytes {start}-{end}/{length}` format.
+fn get_content_range_start(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?
+        .split_once('-')?
+        .0
+        .parse()
+        .ok()
+}
+
+/// Get the complete length of the resource from a `Content-Range` header in the `bytes {range}/{length}` format.
+fn get_content_range_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?
+        .rsplit_once('/')?
+        .1
+        .parse()
+        .ok()
+}
+
+/// Check whether the given error was caused by the response body being cut off or stalling part way through.
+fn is_interrupted_body(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|err| err.is_body() || err.is_timeout())
+    })
+}
+
+/// Delete a partial download and its metadata so the next download starts from scratch.
+fn discard_partial_download(part_path: &Path, part_metadata_path: &Path) {
+    let _ = fs::remove_file(part_path);
+    let _ = fs::remove_file(part_metadata_path);
+}
+
+/// Append an extension to the given path without replacing any existing one.
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoopReporter;
+    use tempfile::TempDir;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    const BODY: &str = "release archive contents";
+    const ETAG_VALUE: &str = "\"v1\"";
+
+    /// Serve [`BODY`] from a local listener that supports range requests, returning the URL of the stand-in resource.
+    async fn serve_resource() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&buf[..read]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let range_start = request
+                    .lines()
+                    .find_map(|line| line.strip_prefix("range: bytes="))
+                    .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok());
+                let response = match range_start {
+                    Some(start) if start >= BODY.len() => format!(
+                        "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        BODY.len()
+                    ),
+                    Some(start) => format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {start}-{}/{}\r\nETag: {ETAG_VALUE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        BODY.len() - 1,
+                        BODY.len(),
+                        BODY.len() - start,
+                        &BODY[start..]
+                    ),
+                    None => format!(
+                        "HTTP/1.1 200 OK\r\nETag: {ETAG_VALUE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{BODY}",
+                        BODY.len()
+                    ),
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{address}/release.zip")
+    }
+
+    /// Write a partial download of the given resource with the given contents next to the given download path.
+    fn write_partial_download(resource: &RemoteResource, path: &Path, contents: &str) {
+        let part_path = append_extension(path, PART_EXTENSION);
+        fs::write(&part_path, contents).unwrap();
+        PartialDownload {
+            url: resource.url.clone(),
+            validator: Some(ETAG_VALUE.to_owned()),
+        }
+        .write_to_path(&append_extension(&part_path, PART_METADATA_EXTENSION))
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn resumes_partial_downloads() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("release.zip");
+        let resource = RemoteResource::from_url(serve_resource().await);
+        write_partial_download(&resource, &path, &BODY[..7]);
+
+        resource
+            .download_with_progress(&path, &NoopReporter)
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), BODY);
+    }
+
+    #[tokio::test]
+    async fn completes_finished_partial_downloads() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("release.zip");
+        let resource = RemoteResource::from_url(serve_resource().await);
+        write_partial_download(&resource, &path, BODY);
+
+        resource
+            .download_with_progress(&path, &NoopReporter)
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), BODY);
+        assert!(!append_extension(&path, PART_EXTENSION).exists());
+    }
+
+    #[tokio::test]
+    async fn restarts_unsatisfiable_partial_downloads() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("release.zip");
+        let resource = RemoteResource::from_url(serve_resource().await);
+        write_partial_download(&resource, &path, &format!("{BODY} and trailing data"));
+
+        resource
+            .download_with_progress(&path, &NoopReporter)
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), BODY);
     }
 }
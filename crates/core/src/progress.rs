@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::{
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The kind of work a progress task represents.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgressKind {
+    /// Downloading a remote resource, progress is measured in bytes.
+    Download,
+    /// Extracting a release archive.
+    Extract,
+}
+
+/// An update about the progress of a long running task.
+///
+/// Every task is identified by an id that is unique for the lifetime of the process, so that multiple tasks running
+/// at the same time can be told apart.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum ProgressEvent {
+    /// A task was started.
+    Started {
+        id: u64,
+        kind: ProgressKind,
+        /// A human readable name of what the task is working on, such as a URL or branch name.
+        name: String,
+        /// The total amount of work the task has to do, if known.
+        total: Option<u64>,
+        /// The total amount of entries the task has to process, for tasks that work on individual entries.
+        entries: Option<u64>,
+    },
+    /// A task has made progress.
+    Progressed {
+        id: u64,
+        /// The amount of work that has been done so far.
+        position: u64,
+    },
+    /// A task has processed an entry, such as a file extracted from an archive.
+    Entry {
+        id: u64,
+        /// The path of the entry.
+        path: String,
+        /// The size of the entry.
+        size: u64,
+        /// The amount of entries that have been processed so far, including this one.
+        processed: u64,
+    },
+    /// A task has finished.
+    Finished {
+        id: u64,
+        /// Whether the task completed, as opposed to being stopped part way through by an error.
+        success: bool,
+    },
+    /// Something went wrong that doesn't stop the current operation.
+    Warning { message: String },
+}
+
+/// A receiver of progress updates for long running tasks, such as downloads and extraction, and of warnings.
+///
+/// A reporter is given to every operation that reports progress, [`NoopReporter`] can be used when nothing should be
+/// reported.
+pub trait ProgressReporter: Send + Sync {
+    /// Handle a single progress update.
+    fn report(&self, event: ProgressEvent);
+
+    /// Report a warning about something that went wrong without stopping the current operation.
+    fn warn(&self, message: &str) {
+        self.report(ProgressEvent::Warning {
+            message: message.to_owned(),
+        });
+    }
+}
+
+/// A [`ProgressReporter`] that discards every update and warning.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopReporter;
+
+impl ProgressReporter for NoopReporter {
+    fn report(&self, _event: ProgressEvent) {}
+}
+
+/// A handle to a running task that reports its progress, reporting it as unsuccessful if dropped before being finished.
+pub(crate) struct ProgressTask<'a> {
+    reporter: &'a dyn ProgressReporter,
+    id: u64,
+    position: u64,
+    processed_entries: u64,
+    finished: bool,
+}
+
+impl<'a> ProgressTask<'a> {
+    /// Start a new task of the given kind that reports to the given reporter.
+    pub(crate) fn start(
+        reporter: &'a dyn ProgressReporter,
+        kind: ProgressKind,
+        name: &str,
+        total: Option<u64>,
+        entries: Option<u64>,
+    ) -> Self {
+        let id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+        reporter.report(ProgressEvent::Started {
+            id,
+            kind,
+            name: name.to_owned(),
+            total,
+            entries,
+        });
+        Self {
+            reporter,
+            id,
+            position: 0,
+            processed_entries: 0,
+            finished: false,
+        }
+    }
+
+    /// Set the amount of work that has been done so far.
+    pub(crate) fn set_position(&mut self, position: u64) {
+        self.position = position;
+        self.reporter.report(ProgressEvent::Progressed {
+            id: self.id,
+            position,
+        });
+    }
+
+    /// Add to the amount of work that has been done so far.
+    pub(crate) fn inc(&mut self, delta: u64) {
+        self.set_position(self.position + delta);
+    }
+
+    /// Report that an entry of the given size has been processed, adding its size to the amount of work that has been
+    /// done so far.
+    pub(crate) fn entry(&mut self, path: &Path, size: u64) {
+        self.processed_entries += 1;
+        self.reporter.report(ProgressEvent::Entry {
+            id: self.id,
+            path: path.to_string_lossy().into_owned(),
+            size,
+            processed: self.processed_entries,
+        });
+        self.inc(size);
+    }
+
+    /// Mark the task as successfully completed.
+    pub(crate) fn finish(mut self) {
+        self.finished = true;
+        self.reporter.report(ProgressEvent::Finished {
+            id: self.id,
+            success: true,
+        });
+    }
+}
+
+impl Drop for ProgressTask<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.reporter.report(ProgressEvent::Finished {
+                id: self.id,
+                success: false,
+            });
+        }
+    }
+}